@@ -0,0 +1,131 @@
+// Тематические аспекты новостей по словарю ключевых слов (секция `[aspects]`)
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, HashMap};
+use crate::models::{AspectSummary, BitcoinNews};
+
+// Порог, ниже которого оценка аспекта считается нейтральной (как в VADER)
+const NEUTRAL_THRESHOLD: f64 = 0.05;
+
+#[derive(Debug, Clone, PartialEq)]
+enum TokenPattern {
+    Exact(String),
+    Prefix(String), // ключевое слово со звёздочкой: "regulat*"
+}
+
+impl TokenPattern {
+    fn matches(&self, token: &str) -> bool {
+        match self {
+            TokenPattern::Exact(word) => token == word,
+            TokenPattern::Prefix(stem) => token.starts_with(stem.as_str()),
+        }
+    }
+}
+
+/// Упоминания аспекта в тексте: число совпадений и предложения, где они найдены
+#[derive(Debug, Clone, PartialEq)]
+pub struct AspectMatch {
+    pub aspect: String,
+    pub mentions: usize,
+    pub sentences: Vec<String>,
+}
+
+/// Классификатор аспектов: ключевые слова и фразы, `*` в конце слова — любой суффикс
+#[derive(Debug, Clone, Default)]
+pub struct AspectClassifier {
+    aspects: Vec<(String, Vec<Vec<TokenPattern>>)>,
+}
+
+impl AspectClassifier {
+    pub fn new(taxonomy: &HashMap<String, Vec<String>>) -> Self {
+        let mut aspects: Vec<(String, Vec<Vec<TokenPattern>>)> = taxonomy.iter()
+            .map(|(aspect, keywords)| {
+                let phrases = keywords.iter()
+                    .map(|keyword| parse_phrase(keyword))
+                    .filter(|phrase| !phrase.is_empty())
+                    .collect();
+                (aspect.to_lowercase(), phrases)
+            })
+            .collect();
+        aspects.sort_by(|a, b| a.0.cmp(&b.0));
+        AspectClassifier { aspects }
+    }
+
+    pub fn aspect_names(&self) -> Vec<String> {
+        self.aspects.iter().map(|(aspect, _)| aspect.clone()).collect()
+    }
+
+    /// Аспекты, упомянутые в тексте, в алфавитном порядке
+    pub fn classify(&self, text: &str) -> Vec<AspectMatch> {
+        let sentences: Vec<(&str, Vec<String>)> = text
+            .split(['.', '!', '?', '\n'])
+            .map(str::trim)
+            .filter(|sentence| !sentence.is_empty())
+            .map(|sentence| (sentence, tokenize(sentence)))
+            .collect();
+
+        self.aspects.iter()
+            .filter_map(|(aspect, phrases)| {
+                let mut mentions = 0;
+                let mut matched = Vec::new();
+                for (sentence, tokens) in &sentences {
+                    let count: usize = phrases.iter().map(|phrase| count_matches(tokens, phrase)).sum();
+                    if count > 0 {
+                        mentions += count;
+                        matched.push(sentence.to_string());
+                    }
+                }
+                (mentions > 0).then(|| AspectMatch { aspect: aspect.clone(), mentions, sentences: matched })
+            })
+            .collect()
+    }
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn parse_phrase(keyword: &str) -> Vec<TokenPattern> {
+    keyword.split(|c: char| !c.is_alphanumeric() && c != '*')
+        .filter(|token| !token.trim_matches('*').is_empty())
+        .map(|token| match token.strip_suffix('*') {
+            Some(stem) => TokenPattern::Prefix(stem.to_lowercase()),
+            None => TokenPattern::Exact(token.to_lowercase()),
+        })
+        .collect()
+}
+
+fn count_matches(tokens: &[String], phrase: &[TokenPattern]) -> usize {
+    if phrase.is_empty() || tokens.len() < phrase.len() {
+        return 0;
+    }
+    tokens.windows(phrase.len())
+        .filter(|window| window.iter().zip(phrase).all(|(token, pattern)| pattern.matches(token)))
+        .count()
+}
+
+/// Сводка по аспектам: сколько статей затрагивают аспект и с каким средним настроением.
+/// Сортировка по числу статей, затем по названию
+pub fn summarize_aspects(news_items: &[BitcoinNews]) -> Vec<AspectSummary> {
+    let mut by_aspect: BTreeMap<&str, Vec<f64>> = BTreeMap::new();
+    for news in news_items {
+        for aspect in &news.aspects {
+            by_aspect.entry(aspect.aspect.as_str()).or_default().push(aspect.score);
+        }
+    }
+
+    let mut summaries: Vec<AspectSummary> = by_aspect.into_iter()
+        .map(|(aspect, scores)| AspectSummary {
+            aspect: aspect.to_string(),
+            article_count: scores.len(),
+            positive_count: scores.iter().filter(|&&s| s >= NEUTRAL_THRESHOLD).count(),
+            negative_count: scores.iter().filter(|&&s| s <= -NEUTRAL_THRESHOLD).count(),
+            average_score: scores.iter().sum::<f64>() / scores.len() as f64,
+            share_of_articles: scores.len() as f64 / news_items.len() as f64 * 100.0,
+        })
+        .collect();
+    summaries.sort_by_key(|summary| Reverse(summary.article_count));
+    summaries
+}
@@ -0,0 +1,228 @@
+use std::collections::BTreeMap;
+use chrono::{Days, NaiveDate};
+
+use crate::models::{BitcoinNews, BitcoinPrice, CorrelationAnalysis, LagCorrelation};
+
+// Лаги в днях: отрицательные — цена опережает настроения, положительные — настроения опережают цену
+const CORRELATION_LAGS: [i64; 7] = [-3, -2, -1, 0, 1, 2, 3];
+// Минимальное количество пар наблюдений для расчета корреляции
+const MIN_SAMPLE_SIZE: usize = 3;
+
+/// Средний sentiment по дням публикации (+1 позитивная, -1 негативная, 0 нейтральная)
+pub fn daily_sentiment_scores(news_items: &[BitcoinNews]) -> BTreeMap<NaiveDate, f64> {
+    let mut buckets: BTreeMap<NaiveDate, (f64, usize)> = BTreeMap::new();
+
+    for news in news_items {
+        let Some(date) = news.published_date() else {
+            continue;
+        };
+        let bucket = buckets.entry(date).or_insert((0.0, 0));
+        bucket.0 += news.sentiment_value();
+        bucket.1 += 1;
+    }
+
+    buckets.into_iter()
+        .map(|(date, (sum, count))| (date, sum / count as f64))
+        .collect()
+}
+
+/// Дневные лог-доходности; доходность дня t считается от закрытия t-1 до закрытия t
+pub fn daily_log_returns(prices: &[BitcoinPrice]) -> BTreeMap<NaiveDate, f64> {
+    let mut sorted: Vec<&BitcoinPrice> = prices.iter().collect();
+    sorted.sort_by_key(|p| p.date);
+
+    sorted.windows(2)
+        .filter(|pair| pair[0].price > 0.0 && pair[1].price > 0.0)
+        .map(|pair| (pair[1].date, (pair[1].price / pair[0].price).ln()))
+        .collect()
+}
+
+/// Корреляция дневных настроений с доходностями на нескольких лагах
+pub fn analyze_sentiment_price_correlation(prices: &[BitcoinPrice], news_items: &[BitcoinNews]) -> CorrelationAnalysis {
+    let sentiment = daily_sentiment_scores(news_items);
+    let returns = daily_log_returns(prices);
+
+    let lags: Vec<LagCorrelation> = CORRELATION_LAGS.iter()
+        .map(|&lag| {
+            let (xs, ys): (Vec<f64>, Vec<f64>) = sentiment.iter()
+                .filter_map(|(date, &score)| {
+                    let target = shift_date(*date, lag)?;
+                    returns.get(&target).map(|&ret| (score, ret))
+                })
+                .unzip();
+
+            LagCorrelation {
+                lag_days: lag,
+                pearson: pearson(&xs, &ys),
+                spearman: spearman(&xs, &ys),
+                sample_size: xs.len(),
+            }
+        })
+        .collect();
+
+    let strongest_lag_days = lags.iter()
+        .filter_map(|l| l.pearson.map(|r| (l.lag_days, r.abs())))
+        .max_by(|a, b| a.1.total_cmp(&b.1))
+        .map(|(lag, _)| lag);
+
+    CorrelationAnalysis {
+        days_with_news: sentiment.len(),
+        days_with_returns: returns.len(),
+        lags,
+        strongest_lag_days,
+    }
+}
+
+/// Коэффициент корреляции Пирсона; None при недостатке данных или нулевой дисперсии
+pub fn pearson(xs: &[f64], ys: &[f64]) -> Option<f64> {
+    if xs.len() != ys.len() || xs.len() < MIN_SAMPLE_SIZE {
+        return None;
+    }
+
+    let n = xs.len() as f64;
+    let mean_x = xs.iter().sum::<f64>() / n;
+    let mean_y = ys.iter().sum::<f64>() / n;
+
+    let mut covariance = 0.0;
+    let mut variance_x = 0.0;
+    let mut variance_y = 0.0;
+    for (x, y) in xs.iter().zip(ys) {
+        covariance += (x - mean_x) * (y - mean_y);
+        variance_x += (x - mean_x).powi(2);
+        variance_y += (y - mean_y).powi(2);
+    }
+
+    if variance_x <= f64::EPSILON || variance_y <= f64::EPSILON {
+        return None;
+    }
+
+    Some(covariance / (variance_x.sqrt() * variance_y.sqrt()))
+}
+
+/// Ранговая корреляция Спирмена (Пирсон по рангам, одинаковые значения получают средний ранг)
+pub fn spearman(xs: &[f64], ys: &[f64]) -> Option<f64> {
+    if xs.len() != ys.len() {
+        return None;
+    }
+    pearson(&ranks(xs), &ranks(ys))
+}
+
+fn ranks(values: &[f64]) -> Vec<f64> {
+    let mut indexed: Vec<(usize, f64)> = values.iter().copied().enumerate().collect();
+    indexed.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+    let mut ranks = vec![0.0; values.len()];
+    let mut i = 0;
+    while i < indexed.len() {
+        let mut j = i;
+        while j + 1 < indexed.len() && indexed[j + 1].1 == indexed[i].1 {
+            j += 1;
+        }
+        let average_rank = (i + j) as f64 / 2.0 + 1.0;
+        for item in &indexed[i..=j] {
+            ranks[item.0] = average_rank;
+        }
+        i = j + 1;
+    }
+    ranks
+}
+
+fn shift_date(date: NaiveDate, lag: i64) -> Option<NaiveDate> {
+    if lag >= 0 {
+        date.checked_add_days(Days::new(lag as u64))
+    } else {
+        date.checked_sub_days(Days::new(lag.unsigned_abs()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prices(values: &[f64]) -> Vec<BitcoinPrice> {
+        values.iter()
+            .enumerate()
+            .map(|(i, &price)| BitcoinPrice {
+                date: NaiveDate::from_ymd_opt(2025, 8, 10 + i as u32).unwrap(),
+                price,
+            })
+            .collect()
+    }
+
+    fn news(day: u32, is_positive: Option<bool>) -> BitcoinNews {
+        BitcoinNews {
+            title: format!("news {}", day),
+            is_positive,
+            published_at: Some(format!("2025-08-{:02}T09:00:00Z", day)),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn pearson_and_spearman_on_monotonic_series() {
+        assert!((pearson(&[1.0, 2.0, 3.0], &[2.0, 4.0, 6.0]).unwrap() - 1.0).abs() < 1e-12);
+        assert!((pearson(&[1.0, 2.0, 3.0], &[3.0, 2.0, 1.0]).unwrap() + 1.0).abs() < 1e-12);
+        assert!((spearman(&[1.0, 2.0, 3.0, 4.0], &[1.0, 8.0, 27.0, 64.0]).unwrap() - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn degenerate_inputs_have_no_correlation() {
+        assert!(pearson(&[], &[]).is_none());
+        assert!(pearson(&[1.0, 2.0], &[1.0, 2.0]).is_none());
+        assert!(pearson(&[1.0, 2.0, 3.0], &[1.0, 2.0]).is_none());
+        assert!(pearson(&[1.0, 1.0, 1.0], &[1.0, 2.0, 3.0]).is_none());
+        assert!(spearman(&[1.0, 2.0, 3.0], &[1.0, 2.0]).is_none());
+    }
+
+    #[test]
+    fn tied_values_share_average_rank() {
+        assert_eq!(ranks(&[10.0, 20.0, 10.0, 30.0]), vec![1.5, 3.0, 1.5, 4.0]);
+    }
+
+    #[test]
+    fn sentiment_leading_price_shows_on_positive_lag() {
+        // Настроения дня d совпадают по знаку с доходностью дня d + 1
+        let prices = prices(&[100.0, 105.0, 100.0, 104.0, 99.0, 103.0]);
+        let news: Vec<BitcoinNews> = [true, false, true, false, true]
+            .iter()
+            .enumerate()
+            .map(|(i, &positive)| news(10 + i as u32, Some(positive)))
+            .collect();
+
+        let analysis = analyze_sentiment_price_correlation(&prices, &news);
+        assert_eq!(analysis.days_with_news, 5);
+        assert_eq!(analysis.days_with_returns, 5);
+        let next_day = analysis.lags.iter().find(|l| l.lag_days == 1).unwrap();
+        assert_eq!(next_day.sample_size, 5);
+        assert!(next_day.pearson.unwrap() > 0.9);
+        let same_day = analysis.lags.iter().find(|l| l.lag_days == 0).unwrap();
+        assert!(same_day.pearson.unwrap() < -0.9);
+    }
+
+    #[test]
+    fn empty_inputs_produce_empty_lags() {
+        let analysis = analyze_sentiment_price_correlation(&[], &[]);
+        assert_eq!(analysis.days_with_news, 0);
+        assert_eq!(analysis.days_with_returns, 0);
+        assert_eq!(analysis.lags.len(), CORRELATION_LAGS.len());
+        assert!(analysis.lags.iter().all(|l| l.sample_size == 0 && l.pearson.is_none() && l.spearman.is_none()));
+        assert_eq!(analysis.strongest_lag_days, None);
+    }
+
+    #[test]
+    fn undated_news_and_invalid_prices_are_ignored() {
+        let mut undated = news(10, Some(true));
+        undated.published_at = None;
+        assert!(daily_sentiment_scores(&[undated]).is_empty());
+
+        let returns = daily_log_returns(&prices(&[100.0, 0.0, 110.0, 121.0]));
+        assert_eq!(returns.len(), 1);
+        assert!((returns.values().next().unwrap() - 1.1f64.ln()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn neutral_news_pull_daily_score_towards_zero() {
+        let scores = daily_sentiment_scores(&[news(10, Some(true)), news(10, None), news(10, Some(false)), news(11, Some(true))]);
+        assert_eq!(scores.values().copied().collect::<Vec<_>>(), vec![0.0, 1.0]);
+    }
+}
@@ -0,0 +1,293 @@
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+use crate::config::DedupConfig;
+use crate::models::BitcoinNews;
+
+// Параметры отслеживания, которые не влияют на содержимое страницы
+const TRACKING_PARAMS: [&str; 8] = ["fbclid", "gclid", "mc_cid", "mc_eid", "ref", "source", "cmpid", "guccounter"];
+
+/// URL без трекинговых параметров, фрагмента, www. и завершающего слэша
+pub fn canonicalize_url(raw: &str) -> Option<String> {
+    let mut url = reqwest::Url::parse(raw.trim()).ok()?;
+    url.set_fragment(None);
+
+    let mut params: Vec<(String, String)> = url.query_pairs()
+        .filter(|(key, _)| {
+            let key = key.to_lowercase();
+            !key.starts_with("utm_") && !TRACKING_PARAMS.contains(&key.as_str())
+        })
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    params.sort();
+
+    let host = url.host_str()?.to_lowercase();
+    let host = host.trim_start_matches("www.");
+    let path = url.path().trim_end_matches('/');
+    let query = params.iter()
+        .map(|(key, value)| format!("{}={}", key, value))
+        .collect::<Vec<_>>()
+        .join("&");
+
+    Some(if query.is_empty() {
+        format!("{}{}", host, path)
+    } else {
+        format!("{}{}?{}", host, path, query)
+    })
+}
+
+/// Заголовок в нижнем регистре без пунктуации и суффикса издания ("... - CoinDesk")
+pub fn normalize_title(title: &str) -> String {
+    let mut title = title.trim();
+    for separator in [" - ", " | ", " — "] {
+        if let Some(pos) = title.rfind(separator) {
+            let suffix = &title[pos + separator.len()..];
+            if pos > 0 && suffix.split_whitespace().count() <= 3 {
+                title = &title[..pos];
+            }
+        }
+    }
+
+    title.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Словесные шинглы длины `size` по нормализованному тексту
+pub fn shingles(text: &str, size: usize) -> HashSet<String> {
+    let words: Vec<String> = text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(|word| word.to_string())
+        .collect();
+
+    if words.len() <= size {
+        return std::iter::once(words.join(" ")).filter(|s| !s.is_empty()).collect();
+    }
+    words.windows(size).map(|window| window.join(" ")).collect()
+}
+
+/// MinHash-сигнатура множества шинглов; у пустого множества сигнатуры нет
+pub fn minhash_signature(shingles: &HashSet<String>, num_hashes: usize) -> Option<Vec<u64>> {
+    if shingles.is_empty() {
+        return None;
+    }
+    let signature = (0..num_hashes as u64)
+        .map(|seed| {
+            shingles.iter()
+                .map(|shingle| {
+                    let mut hasher = DefaultHasher::new();
+                    seed.hash(&mut hasher);
+                    shingle.hash(&mut hasher);
+                    hasher.finish()
+                })
+                .min()
+                .unwrap_or(u64::MAX)
+        })
+        .collect();
+    Some(signature)
+}
+
+/// Оценка коэффициента Жаккара по доле совпавших позиций сигнатур.
+/// Текст без шинглов не похож ни на что, в том числе на другой пустой текст
+pub fn estimated_similarity(a: Option<&[u64]>, b: Option<&[u64]>) -> f64 {
+    let (Some(a), Some(b)) = (a, b) else {
+        return 0.0;
+    };
+    if a.is_empty() || a.len() != b.len() {
+        return 0.0;
+    }
+    a.iter().zip(b).filter(|(x, y)| x == y).count() as f64 / a.len() as f64
+}
+
+fn article_signature(news: &BitcoinNews, config: &DedupConfig) -> Option<Vec<u64>> {
+    let text = format!("{} {}", normalize_title(&news.title), news.content);
+    minhash_signature(&shingles(&text, config.shingle_size), config.num_hashes)
+}
+
+/// Удаляет дубликаты: совпадающий канонический URL, нормализованный заголовок
+/// или почти совпадающий текст. Первая копия остается, у нее растет `duplicate_count`.
+pub fn deduplicate(news_items: Vec<BitcoinNews>, config: &DedupConfig) -> (Vec<BitcoinNews>, usize) {
+    let mut kept: Vec<BitcoinNews> = Vec::new();
+    let mut signatures: Vec<Option<Vec<u64>>> = Vec::new();
+    let mut by_url: HashMap<String, usize> = HashMap::new();
+    let mut by_title: HashMap<String, usize> = HashMap::new();
+    let mut removed = 0;
+
+    for news in news_items {
+        let url_key = news.url.as_deref().and_then(canonicalize_url);
+        let title_key = Some(normalize_title(&news.title)).filter(|t| !t.is_empty());
+        let signature = article_signature(&news, config);
+
+        let duplicate_of = url_key.as_ref().and_then(|key| by_url.get(key).copied())
+            .or_else(|| title_key.as_ref().and_then(|key| by_title.get(key).copied()))
+            .or_else(|| {
+                signatures.iter()
+                    .position(|other| {
+                        estimated_similarity(signature.as_deref(), other.as_deref()) >= config.near_duplicate_threshold
+                    })
+            });
+
+        match duplicate_of {
+            Some(index) => {
+                kept[index].duplicate_count += 1 + news.duplicate_count;
+                removed += 1;
+            }
+            None => {
+                let index = kept.len();
+                if let Some(key) = url_key {
+                    by_url.insert(key, index);
+                }
+                if let Some(key) = title_key {
+                    by_title.insert(key, index);
+                }
+                signatures.push(signature);
+                kept.push(news);
+            }
+        }
+    }
+
+    (kept, removed)
+}
+
+/// Группирует новости в сюжеты и проставляет `story_id` (нумерация по первому появлению)
+pub fn assign_stories(news_items: &mut [BitcoinNews], config: &DedupConfig) -> usize {
+    let signatures: Vec<Option<Vec<u64>>> = news_items.iter().map(|n| article_signature(n, config)).collect();
+    let title_signatures: Vec<Option<Vec<u64>>> = news_items.iter()
+        .map(|n| minhash_signature(&shingles(&normalize_title(&n.title), 1), config.num_hashes))
+        .collect();
+
+    let mut parent: Vec<usize> = (0..news_items.len()).collect();
+    for i in 0..news_items.len() {
+        for j in i + 1..news_items.len() {
+            let similar = estimated_similarity(signatures[i].as_deref(), signatures[j].as_deref()) >= config.story_similarity_threshold
+                || estimated_similarity(title_signatures[i].as_deref(), title_signatures[j].as_deref())
+                    >= config.story_similarity_threshold;
+            if similar {
+                let (root_i, root_j) = (find(&mut parent, i), find(&mut parent, j));
+                parent[root_i.max(root_j)] = root_i.min(root_j);
+            }
+        }
+    }
+
+    let mut story_ids: HashMap<usize, usize> = HashMap::new();
+    for (i, news) in news_items.iter_mut().enumerate() {
+        let root = find(&mut parent, i);
+        let next_id = story_ids.len();
+        news.story_id = Some(*story_ids.entry(root).or_insert(next_id));
+    }
+    story_ids.len()
+}
+
+fn find(parent: &mut [usize], i: usize) -> usize {
+    let mut root = i;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    let mut current = i;
+    while parent[current] != root {
+        let next = parent[current];
+        parent[current] = root;
+        current = next;
+    }
+    root
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONTENT: &str = "Bitcoin climbed above seventy thousand dollars on Tuesday as spot ETF inflows accelerated and traders covered shorts";
+
+    fn news(title: &str, url: Option<&str>, content: &str) -> BitcoinNews {
+        BitcoinNews {
+            title: title.to_string(),
+            content: content.to_string(),
+            url: url.map(str::to_string),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn canonical_url_drops_tracking_and_cosmetic_parts() {
+        assert_eq!(
+            canonicalize_url("https://www.CoinDesk.com/markets/btc/?utm_source=x&b=2&a=1#top"),
+            Some("coindesk.com/markets/btc?a=1&b=2".to_string())
+        );
+        assert_eq!(canonicalize_url("not a url"), None);
+        assert_eq!(canonicalize_url(""), None);
+    }
+
+    #[test]
+    fn title_normalization_strips_publisher_suffix() {
+        assert_eq!(normalize_title("Bitcoin Hits $70K! - CoinDesk"), "bitcoin hits 70k");
+        // Длинная часть после разделителя — часть заголовка, а не издание
+        assert_eq!(normalize_title("Bitcoin - the rally nobody expected this year"), "bitcoin the rally nobody expected this year");
+        assert_eq!(normalize_title("  ?! "), "");
+    }
+
+    #[test]
+    fn short_text_is_a_single_shingle() {
+        assert_eq!(shingles("Bitcoin ETF", 3), HashSet::from(["bitcoin etf".to_string()]));
+        assert_eq!(shingles("a b c d", 3).len(), 2);
+        assert!(shingles("", 3).is_empty());
+        assert!(shingles(" — !", 3).is_empty());
+    }
+
+    #[test]
+    fn empty_shingle_set_has_no_signature() {
+        assert_eq!(minhash_signature(&HashSet::new(), 64), None);
+        let signature = minhash_signature(&shingles(CONTENT, 3), 64).unwrap();
+        assert_eq!(signature.len(), 64);
+        assert_eq!(estimated_similarity(Some(&signature), Some(&signature)), 1.0);
+        assert_eq!(estimated_similarity(None, None), 0.0);
+        assert_eq!(estimated_similarity(Some(&signature), None), 0.0);
+    }
+
+    #[test]
+    fn duplicates_by_url_title_and_text_are_removed() {
+        let items = vec![
+            news("Bitcoin hits $70K", Some("https://coindesk.com/btc-70k?utm_campaign=rss"), CONTENT),
+            news("Bitcoin hits $70K", Some("https://www.coindesk.com/btc-70k/"), CONTENT),
+            news("Bitcoin Hits $70K - Decrypt", Some("https://decrypt.co/btc"), "Different wording of the rally"),
+            news("Bitcoin hits 70K as ETF inflows accelerate", Some("https://cointelegraph.com/btc"), CONTENT),
+            news("Miners sell reserves after halving", Some("https://theblock.co/miners"), "Hashprice fell to record lows this week"),
+        ];
+
+        let config = DedupConfig::default();
+        let (mut unique, removed) = deduplicate(items, &config);
+        assert_eq!(removed, 2);
+        assert_eq!(unique.len(), 3);
+        assert_eq!(unique[0].duplicate_count, 2);
+
+        // Перепечатка с другим заголовком попадает в тот же сюжет
+        let stories = assign_stories(&mut unique, &config);
+        assert_eq!(stories, 2);
+        assert_eq!(unique[0].story_id, unique[1].story_id);
+        assert_ne!(unique[0].story_id, unique[2].story_id);
+    }
+
+    #[test]
+    fn empty_items_are_neither_duplicates_nor_one_story() {
+        let config = DedupConfig::default();
+        let items = vec![news("", None, ""), news("!!!", None, "")];
+
+        let (mut unique, removed) = deduplicate(items, &config);
+        assert_eq!(removed, 0);
+        assert_eq!(unique.len(), 2);
+
+        assert_eq!(assign_stories(&mut unique, &config), 2);
+        assert_ne!(unique[0].story_id, unique[1].story_id);
+    }
+
+    #[test]
+    fn empty_input_has_no_stories() {
+        let config = DedupConfig::default();
+        let (mut unique, removed) = deduplicate(Vec::new(), &config);
+        assert_eq!((unique.len(), removed), (0, 0));
+        assert_eq!(assign_stories(&mut unique, &config), 0);
+    }
+}
@@ -0,0 +1,221 @@
+// Извлечение именованных сущностей: справочник (gazetteer) и шаблоны (секция `[entities]`)
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use regex::Regex;
+use crate::config::{EntityConfig, EntityDefinition, EntityPattern};
+use crate::models::{BitcoinNews, EntityMention, EntitySummary};
+
+// Встроенный справочник: (каноническое имя, тип, варианты написания).
+// Варианты без строчных букв (аббревиатуры) сравниваются с учётом регистра
+const BUILTIN_GAZETTEER: &[(&str, &str, &[&str])] = &[
+    ("Binance", "exchange", &["Binance"]),
+    ("Coinbase", "exchange", &["Coinbase"]),
+    ("Kraken", "exchange", &["Kraken"]),
+    ("OKX", "exchange", &["OKX"]),
+    ("Bybit", "exchange", &["Bybit"]),
+    ("Bitfinex", "exchange", &["Bitfinex"]),
+    ("FTX", "exchange", &["FTX"]),
+    ("SEC", "regulator", &["SEC", "Securities and Exchange Commission"]),
+    ("CFTC", "regulator", &["CFTC", "Commodity Futures Trading Commission"]),
+    ("Federal Reserve", "regulator", &["Federal Reserve", "Fed", "FOMC", "ФРС"]),
+    ("ECB", "regulator", &["ECB", "European Central Bank", "ЕЦБ"]),
+    ("DOJ", "regulator", &["DOJ", "Department of Justice"]),
+    ("FCA", "regulator", &["FCA", "Financial Conduct Authority"]),
+    ("Bank of Russia", "regulator", &["Bank of Russia", "Банк России", "ЦБ", "Центробанк"]),
+    ("BlackRock", "company", &["BlackRock"]),
+    ("Fidelity", "company", &["Fidelity"]),
+    ("Grayscale", "company", &["Grayscale"]),
+    ("MicroStrategy", "company", &["MicroStrategy"]),
+    ("Tesla", "company", &["Tesla"]),
+    ("Tether", "company", &["Tether", "USDT"]),
+    ("Gary Gensler", "person", &["Gary Gensler", "Gensler"]),
+    ("Paul Atkins", "person", &["Paul Atkins"]),
+    ("Jerome Powell", "person", &["Jerome Powell", "Powell"]),
+    ("Michael Saylor", "person", &["Michael Saylor", "Saylor"]),
+    ("Changpeng Zhao", "person", &["Changpeng Zhao", "CZ"]),
+    ("Larry Fink", "person", &["Larry Fink"]),
+    ("Donald Trump", "person", &["Donald Trump", "Trump"]),
+    ("IBIT", "etf", &["IBIT", "iShares Bitcoin Trust"]),
+    ("GBTC", "etf", &["GBTC", "Grayscale Bitcoin Trust"]),
+    ("FBTC", "etf", &["FBTC", "Fidelity Wise Origin Bitcoin Fund"]),
+    ("ARKB", "etf", &["ARKB"]),
+    ("BITO", "etf", &["BITO"]),
+];
+
+// Встроенные шаблоны: первая группа захвата — имя сущности
+const BUILTIN_PATTERNS: &[(&str, &str)] = &[
+    ("person", r"\b(?:CEO|CFO|CTO|[Ff]ounder|[Cc]hair(?:man|woman)?|President|Senator|Governor|Commissioner|Secretary)\s+([A-Z][a-z]+(?:\s+[A-Z][a-z]+){1,2})"),
+    ("etf", r"\b([A-Z]{3,5})\s+(?:spot\s+)?ETF\b"),
+    ("asset", r"\$([A-Z]{2,6})\b"),
+];
+
+#[derive(Debug, Clone)]
+struct Alias {
+    tokens: Vec<String>,
+    lowercase: Vec<String>,
+    case_sensitive: bool,
+}
+
+#[derive(Debug, Clone)]
+struct GazetteerEntry {
+    name: String,
+    kind: String,
+    aliases: Vec<Alias>,
+}
+
+/// Извлекатель сущностей: сначала справочник, затем шаблоны для имён, которых в нём нет
+#[derive(Debug, Clone, Default)]
+pub struct EntityExtractor {
+    gazetteer: Vec<GazetteerEntry>,
+    patterns: Vec<(String, Regex)>,
+}
+
+impl EntityExtractor {
+    /// Встроенный справочник и шаблоны, дополненные конфигурацией;
+    /// запись конфигурации с тем же именем заменяет встроенную
+    pub fn new(config: &EntityConfig) -> Self {
+        let mut definitions: Vec<EntityDefinition> = BUILTIN_GAZETTEER.iter()
+            .map(|(name, kind, aliases)| EntityDefinition {
+                name: name.to_string(),
+                kind: kind.to_string(),
+                aliases: aliases.iter().map(|a| a.to_string()).collect(),
+            })
+            .collect();
+        for definition in &config.gazetteer {
+            definitions.retain(|d| d.name != definition.name);
+            definitions.push(definition.clone());
+        }
+
+        let gazetteer = definitions.into_iter()
+            .map(|definition| GazetteerEntry {
+                aliases: definition.aliases.iter()
+                    .chain((!definition.aliases.contains(&definition.name)).then_some(&definition.name))
+                    .map(|alias| Alias {
+                        tokens: tokenize(alias),
+                        lowercase: tokenize(&alias.to_lowercase()),
+                        case_sensitive: is_case_sensitive(alias),
+                    })
+                    .filter(|alias| !alias.tokens.is_empty())
+                    .collect(),
+                name: definition.name,
+                kind: definition.kind,
+            })
+            .collect();
+
+        let builtin = BUILTIN_PATTERNS.iter()
+            .map(|(kind, regex)| EntityPattern { kind: kind.to_string(), regex: regex.to_string() });
+        let patterns = builtin.chain(config.patterns.iter().cloned())
+            .filter_map(|pattern| match Regex::new(&pattern.regex) {
+                Ok(regex) => Some((pattern.kind, regex)),
+                Err(e) => {
+                    tracing::warn!("Пропущен некорректный шаблон сущностей {}: {}", pattern.regex, e);
+                    None
+                }
+            })
+            .collect();
+
+        EntityExtractor { gazetteer, patterns }
+    }
+
+    /// Сущности текста, по убыванию числа упоминаний
+    pub fn extract(&self, text: &str) -> Vec<EntityMention> {
+        let tokens = tokenize(text);
+        let lowercase: Vec<String> = tokens.iter().map(|t| t.to_lowercase()).collect();
+
+        // Длинные варианты сопоставляются первыми, и их токены уже не засчитываются короткими:
+        // "Donald Trump" — одно упоминание, а не два
+        let mut aliases: Vec<(usize, &Alias)> = self.gazetteer.iter()
+            .enumerate()
+            .flat_map(|(index, entry)| entry.aliases.iter().map(move |alias| (index, alias)))
+            .collect();
+        aliases.sort_by_key(|(_, alias)| Reverse(alias.tokens.len()));
+
+        let mut covered = vec![false; tokens.len()];
+        let mut mentions = vec![0usize; self.gazetteer.len()];
+        for (index, alias) in aliases {
+            let len = alias.tokens.len();
+            for start in 0..tokens.len().saturating_sub(len - 1) {
+                let matches = if alias.case_sensitive {
+                    tokens[start..start + len] == alias.tokens[..]
+                } else {
+                    lowercase[start..start + len] == alias.lowercase[..]
+                };
+                if matches && !covered[start..start + len].iter().any(|&c| c) {
+                    covered[start..start + len].iter_mut().for_each(|c| *c = true);
+                    mentions[index] += 1;
+                }
+            }
+        }
+
+        let mut found: Vec<EntityMention> = self.gazetteer.iter()
+            .zip(mentions)
+            .filter(|(_, mentions)| *mentions > 0)
+            .map(|(entry, mentions)| EntityMention { name: entry.name.clone(), kind: entry.kind.clone(), mentions })
+            .collect();
+
+        let mut from_patterns: HashMap<(String, String), usize> = HashMap::new();
+        for (kind, regex) in &self.patterns {
+            for captures in regex.captures_iter(text) {
+                let Some(name) = captures.get(1).or_else(|| captures.get(0)) else { continue };
+                let name = name.as_str().trim().to_string();
+                let known = found.iter().any(|e| e.name.eq_ignore_ascii_case(&name))
+                    || self.gazetteer.iter().any(|entry| entry.aliases.iter().any(|alias| alias.tokens == tokenize(&name)));
+                if !known {
+                    *from_patterns.entry((name, kind.clone())).or_default() += 1;
+                }
+            }
+        }
+        found.extend(from_patterns.into_iter().map(|((name, kind), mentions)| EntityMention { name, kind, mentions }));
+
+        found.sort_by(|a, b| b.mentions.cmp(&a.mentions).then_with(|| a.name.cmp(&b.name)));
+        found
+    }
+}
+
+fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Самые упоминаемые сущности: число статей, упоминаний и среднее настроение статей с ними
+pub fn summarize_entities(news_items: &[BitcoinNews], top_n: usize) -> Vec<EntitySummary> {
+    let mut by_entity: HashMap<(&str, &str), (usize, usize, f64)> = HashMap::new();
+    for news in news_items {
+        for entity in &news.entities {
+            let entry = by_entity.entry((entity.name.as_str(), entity.kind.as_str())).or_default();
+            entry.0 += 1;
+            entry.1 += entity.mentions;
+            entry.2 += news.sentiment_value();
+        }
+    }
+
+    let mut summaries: Vec<EntitySummary> = by_entity.into_iter()
+        .map(|((name, kind), (articles, mentions, sentiment))| EntitySummary {
+            name: name.to_string(),
+            kind: kind.to_string(),
+            article_count: articles,
+            mention_count: mentions,
+            average_sentiment: sentiment / articles as f64,
+        })
+        .collect();
+    summaries.sort_by(|a, b| {
+        b.article_count.cmp(&a.article_count)
+            .then_with(|| b.mention_count.cmp(&a.mention_count))
+            .then_with(|| a.name.cmp(&b.name))
+    });
+    summaries.truncate(top_n);
+    summaries
+}
+
+/// Аббревиатуры ("SEC") и короткие слова с заглавной ("Fed") совпадают с обычными словами
+/// ("sec", "fed up"), поэтому ищутся с учётом регистра
+fn is_case_sensitive(alias: &str) -> bool {
+    let letters = alias.chars().filter(|c| c.is_alphabetic()).count();
+    let abbreviation = letters > 1 && !alias.chars().any(char::is_lowercase);
+    let short_word = letters <= 3
+        && !alias.contains(char::is_whitespace)
+        && alias.chars().next().is_some_and(char::is_uppercase);
+    abbreviation || short_word
+}
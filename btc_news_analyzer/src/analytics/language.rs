@@ -0,0 +1,52 @@
+use whatlang::Lang;
+
+// Минимальная длина текста, на которой определение языка достаточно надежно
+const MIN_DETECTION_CHARS: usize = 20;
+// whatlang считает короткие заголовки ненадежными даже при верном ответе,
+// поэтому используем собственный порог уверенности
+const MIN_DETECTION_CONFIDENCE: f64 = 0.15;
+
+/// Код языка ISO 639-1 (или ISO 639-3, если двухбуквенного нет) либо None,
+/// если текст слишком короткий или уверенность определения низкая
+pub fn detect_language(text: &str) -> Option<String> {
+    if text.trim().chars().count() < MIN_DETECTION_CHARS {
+        return None;
+    }
+
+    let info = whatlang::detect(text)?;
+    if info.confidence() < MIN_DETECTION_CONFIDENCE {
+        return None;
+    }
+    Some(iso_639_1(info.lang()).to_string())
+}
+
+/// Код языка по английскому названию ("English" -> "en"), как его указывают GDELT и другие агрегаторы
+pub fn language_code_from_name(name: &str) -> Option<String> {
+    Lang::all().iter()
+        .find(|lang| lang.eng_name().eq_ignore_ascii_case(name.trim()))
+        .map(|lang| iso_639_1(*lang).to_string())
+}
+
+fn iso_639_1(lang: Lang) -> &'static str {
+    match lang {
+        Lang::Eng => "en",
+        Lang::Rus => "ru",
+        Lang::Ukr => "uk",
+        Lang::Deu => "de",
+        Lang::Spa => "es",
+        Lang::Fra => "fr",
+        Lang::Ita => "it",
+        Lang::Por => "pt",
+        Lang::Nld => "nl",
+        Lang::Pol => "pl",
+        Lang::Tur => "tr",
+        Lang::Jpn => "ja",
+        Lang::Kor => "ko",
+        Lang::Cmn => "zh",
+        Lang::Ara => "ar",
+        Lang::Hin => "hi",
+        Lang::Vie => "vi",
+        Lang::Ind => "id",
+        other => other.code(),
+    }
+}
@@ -0,0 +1,23 @@
+pub mod aspects;
+pub mod correlation;
+pub mod dedup;
+pub mod entities;
+pub mod language;
+pub mod query;
+pub mod ranking;
+pub mod readability;
+pub mod relevance;
+pub mod text;
+pub mod timeseries;
+
+pub use aspects::{summarize_aspects, AspectClassifier, AspectMatch};
+pub use correlation::{analyze_sentiment_price_correlation, pearson, spearman};
+pub use dedup::{assign_stories, canonicalize_url, deduplicate, normalize_title};
+pub use entities::{summarize_entities, EntityExtractor};
+pub use language::{detect_language, language_code_from_name};
+pub use query::{Query, QueryMatcher};
+pub use ranking::{rank_news, RankedNews};
+pub use readability::extract_main_text;
+pub use relevance::RelevanceScorer;
+pub use text::clean_text;
+pub use timeseries::{build_sentiment_series, MAX_SERIES_POINTS};
@@ -0,0 +1,437 @@
+// Язык поисковых запросов: фразы, AND/OR/NOT, исключения "-слово", скобки.
+// Запрос компилируется в параметр `q` NewsAPI и в локальный фильтр на регулярных выражениях
+use regex::Regex;
+use crate::errors::{BitcoinAnalysisError, Result};
+
+/// Разобранный запрос
+#[derive(Debug, Clone, PartialEq)]
+pub enum Query {
+    Term(String),
+    Phrase(String),
+    And(Vec<Query>),
+    Or(Vec<Query>),
+    Not(Box<Query>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Phrase(String),
+    Open,
+    Close,
+    And,
+    Or,
+    Not,
+}
+
+impl Query {
+    /// Разбор запроса вида `(bitcoin OR btc OR "spot etf") AND NOT scam -ethereum`.
+    /// Соседние выражения без оператора объединяются через AND; операторы — только в верхнем регистре
+    pub fn parse(input: &str) -> Result<Self> {
+        let tokens = tokenize(input)?;
+        if tokens.is_empty() {
+            return Err(query_error("пустой запрос"));
+        }
+        let mut parser = Parser { tokens, position: 0 };
+        let query = parser.parse_or()?;
+        if parser.position < parser.tokens.len() {
+            return Err(query_error(format!("лишний элемент на позиции {}: {:?}", parser.position + 1, parser.tokens[parser.position])));
+        }
+        if !query.has_positive_terms() {
+            return Err(query_error("запрос должен содержать хотя бы одно искомое слово, а не только исключения"));
+        }
+        Ok(query)
+    }
+
+    /// Запрос «любое из слов» для обратной совместимости со списком `bitcoin_keywords`.
+    /// Пустые ключевые слова пропускаются; если не осталось ни одного, это ошибка
+    pub fn any_of(keywords: &[String]) -> Result<Self> {
+        let mut terms: Vec<Query> = keywords.iter()
+            .map(|k| k.split_whitespace().collect::<Vec<_>>().join(" "))
+            .filter(|k| !k.is_empty())
+            .map(|k| if k.contains(' ') { Query::Phrase(k) } else { Query::Term(k) })
+            .collect();
+        match terms.len() {
+            0 => Err(query_error("список ключевых слов не содержит ни одного непустого слова")),
+            1 => Ok(terms.remove(0)),
+            _ => Ok(Query::Or(terms)),
+        }
+    }
+
+    /// Искомые слова и фразы запроса без исключений
+    pub fn terms(&self) -> Vec<String> {
+        match self {
+            Query::Term(term) | Query::Phrase(term) => vec![term.clone()],
+            Query::And(items) | Query::Or(items) => items.iter().flat_map(Query::terms).collect(),
+            Query::Not(_) => Vec::new(),
+        }
+    }
+
+    fn has_positive_terms(&self) -> bool {
+        match self {
+            Query::Term(_) | Query::Phrase(_) => true,
+            Query::And(items) | Query::Or(items) => items.iter().any(Query::has_positive_terms),
+            Query::Not(_) => false,
+        }
+    }
+
+    /// Проверяет, что запрос выразим в синтаксисе NewsAPI: NOT там означает «и не»,
+    /// поэтому каждая альтернатива OR и каждая группа в скобках должна что-то искать
+    pub fn validate_newsapi(&self) -> Result<()> {
+        match self {
+            Query::Term(_) | Query::Phrase(_) => Ok(()),
+            Query::Or(items) | Query::And(items) => {
+                for item in items {
+                    let grouped = matches!(self, Query::Or(_)) || matches!(item, Query::And(_) | Query::Or(_));
+                    if grouped && !item.has_positive_terms() {
+                        return Err(query_error(format!(
+                            "NewsAPI не поддерживает исключение без искомого слова внутри OR или скобок: {}",
+                            self.render(false)
+                        )));
+                    }
+                    item.validate_newsapi()?;
+                }
+                Ok(())
+            }
+            Query::Not(inner) => inner.validate_newsapi(),
+        }
+    }
+
+    /// Синтаксис параметра `q` NewsAPI: фразы в кавычках, AND/OR, исключения через NOT
+    pub fn to_newsapi(&self) -> String {
+        self.render(true)
+    }
+
+    fn render(&self, top_level: bool) -> String {
+        let wrap = |text: String| if top_level { text } else { format!("({})", text) };
+        match self {
+            Query::Term(term) if term.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') => term.clone(),
+            Query::Term(term) | Query::Phrase(term) => format!("\"{}\"", term.replace('"', "")),
+            Query::Or(items) => wrap(items.iter().map(|q| q.render(false)).collect::<Vec<_>>().join(" OR ")),
+            Query::And(items) => {
+                // NewsAPI понимает NOT как «и не», поэтому исключения идут после искомых слов
+                let positive: Vec<String> = items.iter()
+                    .filter(|q| !matches!(q, Query::Not(_)))
+                    .map(|q| q.render(false))
+                    .collect();
+                let negative: Vec<String> = items.iter()
+                    .filter_map(|q| match q {
+                        Query::Not(inner) => Some(format!("NOT {}", inner.render(false))),
+                        _ => None,
+                    })
+                    .collect();
+                let mut text = positive.join(" AND ");
+                for exclusion in negative {
+                    if !text.is_empty() {
+                        text.push(' ');
+                    }
+                    text.push_str(&exclusion);
+                }
+                wrap(text)
+            }
+            Query::Not(inner) => wrap(format!("NOT {}", inner.render(false))),
+        }
+    }
+
+    /// Проверяет, что запрос выразим в синтаксисе GDELT: OR допускается только между
+    /// словами и фразами, вложенные скобки GDELT отклоняет
+    pub fn validate_gdelt(&self) -> Result<()> {
+        match self {
+            Query::Term(_) | Query::Phrase(_) => Ok(()),
+            Query::Or(items) => match items.iter().find(|q| !matches!(q, Query::Term(_) | Query::Phrase(_))) {
+                Some(_) => Err(query_error(format!(
+                    "GDELT не поддерживает вложенные скобки и исключения внутри OR: {}",
+                    self.render(false)
+                ))),
+                None => Ok(()),
+            },
+            Query::And(items) => items.iter().try_for_each(Query::validate_gdelt),
+            Query::Not(inner) => inner.validate_gdelt(),
+        }
+    }
+
+    /// Синтаксис GDELT DOC API: AND — пробел, OR только в скобках, исключения через "-".
+    /// Исключения составных выражений GDELT не поддерживает, они опускаются
+    pub fn to_gdelt(&self) -> String {
+        self.render_gdelt().unwrap_or_default()
+    }
+
+    fn render_gdelt(&self) -> Option<String> {
+        match self {
+            Query::Term(term) if term.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') => Some(term.clone()),
+            Query::Term(term) | Query::Phrase(term) => Some(format!("\"{}\"", term.replace('"', ""))),
+            Query::Or(items) => {
+                let items: Vec<String> = items.iter().filter_map(Query::render_gdelt).collect();
+                match items.len() {
+                    0 => None,
+                    1 => items.into_iter().next(),
+                    _ => Some(format!("({})", items.join(" OR "))),
+                }
+            }
+            Query::And(items) => {
+                let items: Vec<String> = items.iter().filter_map(Query::render_gdelt).collect();
+                (!items.is_empty()).then(|| items.join(" "))
+            }
+            Query::Not(inner) => match inner.as_ref() {
+                Query::Term(_) | Query::Phrase(_) => inner.render_gdelt().map(|text| format!("-{}", text)),
+                _ => None,
+            },
+        }
+    }
+
+    /// Локальный фильтр: каждое слово и фраза — регулярное выражение с экранированием
+    /// метасимволов, без учёта регистра и с границами слов
+    pub fn matcher(&self) -> Result<QueryMatcher> {
+        Ok(QueryMatcher { root: self.compile()? })
+    }
+
+    fn compile(&self) -> Result<MatchNode> {
+        let pattern = |words: Vec<String>| -> Result<MatchNode> {
+            let body = words.join(r"\s+");
+            Ok(MatchNode::Pattern(Regex::new(&format!(r"(?i)(?:^|\W){}(?:\W|$)", body))?))
+        };
+        match self {
+            Query::Term(term) => pattern(vec![regex::escape(term)]),
+            Query::Phrase(phrase) => pattern(phrase.split_whitespace().map(regex::escape).collect()),
+            Query::And(items) => Ok(MatchNode::And(items.iter().map(Query::compile).collect::<Result<_>>()?)),
+            Query::Or(items) => Ok(MatchNode::Or(items.iter().map(Query::compile).collect::<Result<_>>()?)),
+            Query::Not(inner) => Ok(MatchNode::Not(Box::new(inner.compile()?))),
+        }
+    }
+}
+
+/// Скомпилированный запрос для фильтрации собранных новостей
+#[derive(Debug, Clone)]
+pub struct QueryMatcher {
+    root: MatchNode,
+}
+
+#[derive(Debug, Clone)]
+enum MatchNode {
+    Pattern(Regex),
+    And(Vec<MatchNode>),
+    Or(Vec<MatchNode>),
+    Not(Box<MatchNode>),
+}
+
+impl QueryMatcher {
+    pub fn is_match(&self, text: &str) -> bool {
+        self.root.is_match(text)
+    }
+}
+
+impl MatchNode {
+    fn is_match(&self, text: &str) -> bool {
+        match self {
+            MatchNode::Pattern(regex) => regex.is_match(text),
+            MatchNode::And(items) => items.iter().all(|node| node.is_match(text)),
+            MatchNode::Or(items) => items.iter().any(|node| node.is_match(text)),
+            MatchNode::Not(inner) => !inner.is_match(text),
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn parse_or(&mut self) -> Result<Query> {
+        let mut items = vec![self.parse_and()?];
+        while self.peek() == Some(&Token::Or) {
+            self.position += 1;
+            items.push(self.parse_and()?);
+        }
+        Ok(if items.len() == 1 { items.remove(0) } else { Query::Or(items) })
+    }
+
+    fn parse_and(&mut self) -> Result<Query> {
+        let mut items = vec![self.parse_unary()?];
+        loop {
+            match self.peek() {
+                Some(Token::And) => self.position += 1,
+                Some(Token::Word(_) | Token::Phrase(_) | Token::Open | Token::Not) => {}
+                _ => break,
+            }
+            items.push(self.parse_unary()?);
+        }
+        Ok(if items.len() == 1 { items.remove(0) } else { Query::And(items) })
+    }
+
+    fn parse_unary(&mut self) -> Result<Query> {
+        match self.tokens.get(self.position).cloned() {
+            Some(Token::Not) => {
+                self.position += 1;
+                Ok(Query::Not(Box::new(self.parse_unary()?)))
+            }
+            Some(Token::Open) => {
+                self.position += 1;
+                let inner = self.parse_or()?;
+                if self.peek() != Some(&Token::Close) {
+                    return Err(query_error("не закрыта скобка"));
+                }
+                self.position += 1;
+                Ok(inner)
+            }
+            Some(Token::Word(word)) => {
+                self.position += 1;
+                Ok(Query::Term(word))
+            }
+            Some(Token::Phrase(phrase)) => {
+                self.position += 1;
+                Ok(Query::Phrase(phrase))
+            }
+            Some(token) => Err(query_error(format!("ожидалось слово или фраза, найдено {:?}", token))),
+            None => Err(query_error("запрос обрывается после оператора")),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            '"' => {
+                chars.next();
+                let mut phrase = String::new();
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some(c) => phrase.push(c),
+                        None => return Err(query_error("не закрыта кавычка")),
+                    }
+                }
+                let phrase = phrase.split_whitespace().collect::<Vec<_>>().join(" ");
+                if phrase.is_empty() {
+                    return Err(query_error("пустая фраза"));
+                }
+                tokens.push(Token::Phrase(phrase));
+            }
+            '-' => {
+                // "-ethereum" — исключение; одиночный дефис игнорируется
+                chars.next();
+                if chars.peek().is_some_and(|c| !c.is_whitespace()) {
+                    tokens.push(Token::Not);
+                }
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || c == '(' || c == ')' || c == '"' {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                let word = word.trim_start_matches('+').to_string();
+                tokens.push(match word.as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ if word.is_empty() => continue,
+                    _ => Token::Word(word),
+                });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn query_error(message: impl Into<String>) -> BitcoinAnalysisError {
+    BitcoinAnalysisError::InvalidQuery(message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keywords(words: &[&str]) -> Vec<String> {
+        words.iter().map(|w| w.to_string()).collect()
+    }
+
+    #[test]
+    fn query_compiles_to_newsapi_and_gdelt() {
+        let query = Query::parse(r#"(bitcoin OR btc OR "spot etf") AND NOT scam -ethereum"#).unwrap();
+        assert_eq!(query.to_newsapi(), r#"(bitcoin OR btc OR "spot etf") NOT scam NOT ethereum"#);
+        assert_eq!(query.to_gdelt(), r#"(bitcoin OR btc OR "spot etf") -scam -ethereum"#);
+        assert!(query.validate_newsapi().is_ok());
+        assert!(query.validate_gdelt().is_ok());
+        assert_eq!(query.terms(), ["bitcoin", "btc", "spot etf"]);
+    }
+
+    #[test]
+    fn matcher_uses_word_boundaries_and_exclusions() {
+        let matcher = Query::parse(r#"(bitcoin OR btc OR "spot etf") AND NOT scam -ethereum"#).unwrap().matcher().unwrap();
+        assert!(matcher.is_match("BTC climbs as Spot  ETF inflows grow"));
+        assert!(matcher.is_match("Ждём новостей: bitcoin."));
+        assert!(!matcher.is_match("Bitcoin and Ethereum both rally"));
+        assert!(!matcher.is_match("Bitcoin scam warning"));
+        assert!(!matcher.is_match("Bitcoiners celebrate"));
+        assert!(!matcher.is_match(""));
+    }
+
+    #[test]
+    fn regex_metacharacters_are_escaped() {
+        let matcher = Query::parse("$BTC OR c++").unwrap().matcher().unwrap();
+        assert!(matcher.is_match("Traders pile into $BTC today"));
+        assert!(matcher.is_match("written in c++"));
+        assert!(!matcher.is_match("BTC only"));
+    }
+
+    #[test]
+    fn malformed_queries_are_rejected() {
+        for invalid in ["", "   ", "(bitcoin OR btc", "\"spot etf", "\"  \"", "bitcoin OR", "bitcoin)", "-ethereum", "NOT scam", "NOT (a OR b)"] {
+            assert!(Query::parse(invalid).is_err(), "запрос {:?} должен быть отклонён", invalid);
+        }
+    }
+
+    #[test]
+    fn newsapi_rejects_exclusions_inside_or() {
+        for query in ["bitcoin OR -scam", "bitcoin OR NOT scam", "bitcoin OR (NOT scam -rug)", "bitcoin (NOT scam -rug)"] {
+            let query = Query::parse(query).unwrap();
+            let error = query.validate_newsapi().unwrap_err().to_string();
+            assert!(error.contains("NewsAPI"), "{}", error);
+        }
+        // Исключение рядом с искомым словом внутри скобок выразимо
+        assert!(Query::parse("bitcoin OR (btc -scam)").unwrap().validate_newsapi().is_ok());
+        assert!(Query::parse("bitcoin NOT (scam OR rug)").unwrap().validate_newsapi().is_ok());
+    }
+
+    #[test]
+    fn gdelt_rejects_nested_groups() {
+        for query in ["bitcoin OR (btc etf)", "(bitcoin OR (btc OR xbt)) etf", "bitcoin OR -scam"] {
+            let query = Query::parse(query).unwrap();
+            let error = query.validate_gdelt().unwrap_err().to_string();
+            assert!(error.contains("GDELT"), "{}", error);
+        }
+        assert!(Query::parse(r#"(bitcoin OR "spot etf") (miners OR halving) -scam"#).unwrap().validate_gdelt().is_ok());
+    }
+
+    #[test]
+    fn any_of_skips_blank_keywords() {
+        assert_eq!(
+            Query::any_of(&keywords(&["bitcoin", "  ", "digital   gold"])).unwrap(),
+            Query::Or(vec![Query::Term("bitcoin".to_string()), Query::Phrase("digital gold".to_string())])
+        );
+        assert_eq!(Query::any_of(&keywords(&[" btc ", ""])).unwrap(), Query::Term("btc".to_string()));
+        assert!(Query::any_of(&keywords(&["", " \t "])).is_err());
+        assert!(Query::any_of(&[]).is_err());
+    }
+}
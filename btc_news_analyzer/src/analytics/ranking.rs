@@ -0,0 +1,140 @@
+use std::collections::HashSet;
+use chrono::{DateTime, Utc};
+use regex::Regex;
+
+use crate::config::{DedupConfig, RankingConfig};
+use crate::models::BitcoinNews;
+
+// Нейтральная уверенность, если модель не вернула оценку (например, сработал словарный анализ)
+const UNKNOWN_CONFIDENCE: f64 = 0.5;
+// Компонент считается причиной высокого ранга, если его нормированная оценка не ниже порога
+const REASON_THRESHOLD: f64 = 0.6;
+
+/// Новость с итоговым рангом и его составляющими
+#[derive(Debug, Clone)]
+pub struct RankedNews {
+    pub index: usize, // позиция в исходном списке
+    pub score: f64,
+    pub confidence: f64,
+    pub reasons: Vec<String>,
+}
+
+/// Ранжирует новости по уверенности модели, релевантности ключевым словам,
+/// доверию к источнику, свежести и повторяемости сюжета в разных изданиях.
+/// Повторяемость берётся из сюжетов дедупликации, а без неё — из сходства заголовков
+pub fn rank_news(news_items: &[BitcoinNews], keywords: &[String], config: &RankingConfig, dedup: &DedupConfig) -> Vec<RankedNews> {
+    let keyword_regex = keyword_regex(keywords);
+    let latest = news_items.iter().filter_map(|n| n.published_datetime()).max();
+    let title_tokens: Vec<HashSet<String>> = news_items.iter().map(|n| title_tokens(&n.title)).collect();
+    let domains: Vec<Option<String>> = news_items.iter().map(|n| n.source_domain()).collect();
+    let total_weight = config.confidence_weight
+        + config.relevance_weight
+        + config.source_trust_weight
+        + config.recency_weight
+        + config.recurrence_weight;
+
+    let mut ranked: Vec<RankedNews> = news_items.iter()
+        .enumerate()
+        .map(|(index, news)| {
+            let mut reasons = Vec::new();
+
+            let confidence = news.sentiment_confidence.unwrap_or(UNKNOWN_CONFIDENCE);
+            if confidence >= REASON_THRESHOLD {
+                reasons.push(format!("model confidence {:.2}", confidence));
+            }
+
+            let (title_hits, content_hits) = keyword_regex.as_ref().map_or((0, 0), |re| {
+                (re.find_iter(&news.title).count(), re.find_iter(&news.content).count())
+            });
+            // Оценка релевантности из обработки; без неё совпадение в заголовке весит вдвое больше,
+            // насыщение по экспоненте
+            let relevance = news.relevance
+                .unwrap_or_else(|| 1.0 - (-((title_hits * 2 + content_hits) as f64) / 3.0).exp());
+            if relevance >= REASON_THRESHOLD {
+                reasons.push(format!("relevance {:.2}, {} keyword hits ({} in title)", relevance, title_hits + content_hits, title_hits));
+            }
+
+            let source_trust = domains[index].as_ref()
+                .and_then(|domain| config.source_trust.get(domain))
+                .copied()
+                .unwrap_or(config.default_source_trust);
+            if source_trust >= REASON_THRESHOLD {
+                if let Some(domain) = &domains[index] {
+                    reasons.push(format!("trusted source {} ({:.2})", domain, source_trust));
+                }
+            }
+
+            let recency = match (news.published_datetime(), latest) {
+                (Some(published), Some(latest)) => recency_score(published, latest, config.recency_half_life_hours),
+                _ => 0.0,
+            };
+            if recency >= REASON_THRESHOLD {
+                reasons.push("recent publication".to_string());
+            }
+
+            // Число других изданий, опубликовавших тот же сюжет: копии, удалённые дедупликацией,
+            // и другие домены сюжета; без дедупликации — домены новостей с похожим заголовком
+            let in_same_story = |other: usize| {
+                if dedup.enabled {
+                    news.story_id.is_some() && news_items[other].story_id == news.story_id
+                } else {
+                    jaccard(&title_tokens[index], &title_tokens[other]) >= dedup.story_similarity_threshold
+                }
+            };
+            let other_domains: HashSet<&str> = (0..news_items.len())
+                .filter(|&other| other != index && in_same_story(other))
+                .filter_map(|other| domains[other].as_deref())
+                .filter(|domain| Some(*domain) != domains[index].as_deref())
+                .collect();
+            let other_outlets = other_domains.len() + if dedup.enabled { news.duplicate_count } else { 0 };
+            let recurrence = (other_outlets as f64 / 3.0).min(1.0);
+            if other_outlets > 0 {
+                reasons.push(format!("story covered by {} other outlets", other_outlets));
+            }
+
+            let score = (confidence * config.confidence_weight
+                + relevance * config.relevance_weight
+                + source_trust * config.source_trust_weight
+                + recency * config.recency_weight
+                + recurrence * config.recurrence_weight)
+                / total_weight;
+
+            RankedNews {
+                index,
+                score,
+                confidence,
+                reasons,
+            }
+        })
+        .collect();
+
+    ranked.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.index.cmp(&b.index)));
+    ranked
+}
+
+fn keyword_regex(keywords: &[String]) -> Option<Regex> {
+    if keywords.is_empty() {
+        return None;
+    }
+    let pattern = keywords.iter().map(|k| regex::escape(k)).collect::<Vec<_>>().join("|");
+    Regex::new(&format!(r"(?i)\b({})\b", pattern)).ok()
+}
+
+fn recency_score(published: DateTime<Utc>, latest: DateTime<Utc>, half_life_hours: f64) -> f64 {
+    let age_hours = (latest - published).num_minutes().max(0) as f64 / 60.0;
+    0.5f64.powf(age_hours / half_life_hours)
+}
+
+fn title_tokens(title: &str) -> HashSet<String> {
+    title.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| token.chars().count() > 2)
+        .map(|token| token.to_lowercase())
+        .collect()
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    a.intersection(b).count() as f64 / a.union(b).count() as f64
+}
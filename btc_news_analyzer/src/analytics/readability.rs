@@ -0,0 +1,106 @@
+// Извлечение основного текста статьи из HTML (упрощённый алгоритм Readability)
+use std::collections::HashMap;
+use scraper::{ElementRef, Html, Selector};
+
+// Теги и классы/id контейнеров, текст которых не относится к статье
+const BOILERPLATE_TAGS: &[&str] = &[
+    "nav", "header", "footer", "aside", "form", "script", "style", "noscript", "figcaption", "button",
+];
+const BOILERPLATE_MARKERS: &[&str] = &[
+    "comment", "comments", "footer", "sidebar", "related", "share", "social", "newsletter", "promo", "advert", "cookie", "subscribe",
+];
+// Корни содержимого: их классы (например, `has-cookie-banner` у `<body>`) описывают страницу целиком
+const CONTENT_ROOT_TAGS: &[&str] = &["article", "main", "body", "html"];
+const MIN_PARAGRAPH_CHARS: usize = 25;
+const MAX_LINK_DENSITY: f64 = 0.5;
+const SIBLING_SCORE_RATIO: f64 = 0.2;
+
+/// Основной текст страницы: абзацы блока с наибольшим объёмом текста и его соседей.
+/// Абзац засчитывает свою длину родителю и половину — деду, поэтому статья,
+/// разбитая на несколько `<div>`, собирается целиком. None — подходящих абзацев нет
+pub fn extract_main_text(html: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+    let paragraph_selector = Selector::parse("p").ok()?;
+    let link_selector = Selector::parse("a").ok()?;
+
+    // (текст, родитель, дед); идентификаторы узлов — ego_tree::NodeId
+    let mut paragraphs = Vec::new();
+    let mut scores = HashMap::new();
+    for element in document.select(&paragraph_selector) {
+        let mut ancestors = element.ancestors()
+            .filter_map(ElementRef::wrap)
+            .take_while(|ancestor| !CONTENT_ROOT_TAGS.contains(&ancestor.value().name()));
+        if ancestors.any(is_boilerplate) {
+            continue;
+        }
+        let text = element.text().collect::<Vec<_>>().join(" ");
+        let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+        let length = text.chars().count();
+        if length < MIN_PARAGRAPH_CHARS {
+            continue;
+        }
+        let link_length: usize = element.select(&link_selector)
+            .flat_map(|link| link.text())
+            .map(|t| t.trim().chars().count())
+            .sum();
+        if link_length as f64 / length as f64 > MAX_LINK_DENSITY {
+            continue;
+        }
+
+        let parent = element.parent().map(|node| node.id());
+        let grandparent = element.parent().and_then(|node| node.parent()).map(|node| node.id());
+        if let Some(id) = parent {
+            *scores.entry(id).or_insert(0.0) += length as f64;
+        }
+        if let Some(id) = grandparent {
+            *scores.entry(id).or_insert(0.0) += length as f64 / 2.0;
+        }
+        paragraphs.push((text, parent, grandparent));
+    }
+
+    let (best, best_score) = scores.iter()
+        .map(|(id, score)| (*id, *score))
+        .max_by(|a: &(_, f64), b| a.1.total_cmp(&b.1))?;
+
+    // Соседние блоки с тем же родителем и заметным объёмом текста — продолжение статьи
+    let best_parent = paragraphs.iter()
+        .find(|(_, parent, _)| *parent == Some(best))
+        .and_then(|(_, _, grandparent)| *grandparent);
+    let is_sibling = |parent: &Option<_>, grandparent: &Option<_>| {
+        best_parent.is_some()
+            && *grandparent == best_parent
+            && parent.and_then(|id| scores.get(&id)).is_some_and(|score| *score >= best_score * SIBLING_SCORE_RATIO)
+    };
+
+    let text = paragraphs.iter()
+        .filter(|(_, parent, grandparent)| {
+            *parent == Some(best) || *grandparent == Some(best) || is_sibling(parent, grandparent)
+        })
+        .map(|(text, _, _)| text.as_str())
+        .collect::<Vec<_>>()
+        .join("\n\n");
+    (!text.is_empty()).then_some(text)
+}
+
+fn is_boilerplate(element: ElementRef) -> bool {
+    let value = element.value();
+    if BOILERPLATE_TAGS.contains(&value.name()) {
+        return true;
+    }
+    let markers = format!("{} {}", value.attr("class").unwrap_or_default(), value.id().unwrap_or_default()).to_lowercase();
+    markers.split_whitespace().any(is_boilerplate_token)
+}
+
+/// Класс или id целиком совпадает с маркером либо начинается/заканчивается им через `-` или `_`:
+/// `share`, `share-buttons`, `site-footer`, но не `has-cookie-banner`
+fn is_boilerplate_token(token: &str) -> bool {
+    let separated = |rest: &str, at_start: bool| {
+        let separator = if at_start { rest.chars().next() } else { rest.chars().last() };
+        matches!(separator, Some('-' | '_'))
+    };
+    BOILERPLATE_MARKERS.iter().any(|marker| {
+        token == *marker
+            || token.strip_prefix(marker).is_some_and(|rest| separated(rest, true))
+            || token.strip_suffix(marker).is_some_and(|rest| separated(rest, false))
+    })
+}
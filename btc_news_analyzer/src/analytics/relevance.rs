@@ -0,0 +1,70 @@
+// Релевантность новости теме запроса (секция `[relevance]`)
+use std::cmp::Reverse;
+use regex::Regex;
+use crate::config::RelevanceConfig;
+use crate::models::EntityMention;
+
+/// Оценка релевантности 0.0..1.0: совпадения в заголовке, плотность совпадений в тексте,
+/// позиция первого совпадения и упоминания сущностей нужных типов
+#[derive(Debug, Clone)]
+pub struct RelevanceScorer {
+    terms: Option<Regex>,
+    config: RelevanceConfig,
+}
+
+impl RelevanceScorer {
+    /// `terms` — искомые слова и фразы запроса (`Query::terms`)
+    pub fn new(terms: &[String], config: &RelevanceConfig) -> Self {
+        let mut patterns: Vec<String> = terms.iter()
+            .map(|term| term.split_whitespace().map(regex::escape).collect::<Vec<_>>().join(r"\s+"))
+            .filter(|pattern| !pattern.is_empty())
+            .collect();
+        // Длинные варианты первыми: "bitcoin etf" не должно совпасть как "bitcoin"
+        patterns.sort_by_key(|pattern| Reverse(pattern.len()));
+        patterns.dedup();
+        let terms = (!patterns.is_empty())
+            .then(|| Regex::new(&format!("(?i){}", patterns.join("|"))).ok())
+            .flatten();
+        RelevanceScorer { terms, config: config.clone() }
+    }
+
+    pub fn score(&self, title: &str, text: &str, entities: &[EntityMention]) -> f64 {
+        let config = &self.config;
+        let title_hits = self.hits(title).len();
+        let hits = self.hits(text);
+
+        let title_score = (title_hits as f64).min(1.0);
+
+        let words = text.split_whitespace().count().max(1);
+        let density = hits.len() as f64 * 100.0 / words as f64;
+        let density_score = (density / config.target_density).min(1.0);
+
+        // Тема, упомянутая в начале текста, скорее основная, чем упомянутая вскользь в конце
+        let position_score = hits.first()
+            .map(|&start| 1.0 - start as f64 / text.len().max(1) as f64)
+            .unwrap_or(0.0);
+
+        let entity_mentions: usize = entities.iter()
+            .filter(|entity| config.entity_kinds.contains(&entity.kind))
+            .map(|entity| entity.mentions)
+            .sum();
+        let entity_score = (entity_mentions as f64 / 3.0).min(1.0);
+
+        let total_weight = config.title_weight + config.density_weight + config.position_weight + config.entity_weight;
+        (title_score * config.title_weight
+            + density_score * config.density_weight
+            + position_score * config.position_weight
+            + entity_score * config.entity_weight)
+            / total_weight
+    }
+
+    /// Начала совпадений, окружённых границами слов
+    fn hits(&self, text: &str) -> Vec<usize> {
+        let Some(terms) = &self.terms else { return Vec::new() };
+        let is_word = |c: Option<char>| c.is_some_and(|c| c.is_alphanumeric() || c == '_');
+        terms.find_iter(text)
+            .filter(|m| !is_word(text[..m.start()].chars().next_back()) && !is_word(text[m.end()..].chars().next()))
+            .map(|m| m.start())
+            .collect()
+    }
+}
@@ -0,0 +1,217 @@
+// Нормализация текста новостей перед анализом настроений
+use std::sync::OnceLock;
+use ego_tree::NodeRef;
+use regex::Regex;
+use scraper::{Html, Node};
+use unicode_normalization::UnicodeNormalization;
+
+// Содержимое этих элементов — не текст статьи
+const SKIPPED_TAGS: &[&str] = &["script", "style", "noscript", "template", "iframe", "svg", "object", "head"];
+// Границы блочных элементов превращаются в пробел, чтобы слова соседних абзацев не склеивались
+const BLOCK_TAGS: &[&str] = &[
+    "p", "div", "br", "li", "ul", "ol", "h1", "h2", "h3", "h4", "h5", "h6", "tr", "td", "th",
+    "blockquote", "section", "article", "figure", "figcaption", "pre", "table", "hr", "img",
+];
+// Строчные элементы, которые встречаются в описаниях лент. Только эти имена (и имена
+// из списков выше) считаются тегами, поэтому "price <b and c> a" остаётся текстом
+const INLINE_TAGS: &[&str] = &[
+    "a", "b", "i", "u", "s", "em", "strong", "span", "small", "big", "sub", "sup", "code", "font",
+    "mark", "abbr", "cite", "q", "time", "strike", "del", "ins", "wbr", "center", "picture", "source",
+    "video", "audio", "caption", "thead", "tbody", "tfoot", "dl", "dt", "dd", "header", "footer",
+    "main", "nav", "aside", "html", "body", "meta", "link", "title",
+];
+// Атрибуты без значения, допустимые в тегах; прочие атрибуты должны иметь "=значение"
+const BOOLEAN_ATTRIBUTES: &[&str] = &["allowfullscreen", "async", "autoplay", "controls", "defer", "hidden", "loop", "muted", "nowrap"];
+// Ленты иногда экранируют HTML дважды ("&amp;#8217;", "&lt;p&gt;")
+const MAX_DECODE_PASSES: usize = 3;
+
+struct Patterns {
+    markup: Regex,
+    tag: Regex,
+    cdata: Regex,
+    truncation: Regex,
+    feed_footer: Regex,
+    url: Regex,
+    whitespace: Regex,
+}
+
+fn patterns() -> &'static Patterns {
+    static PATTERNS: OnceLock<Patterns> = OnceLock::new();
+    PATTERNS.get_or_init(|| {
+        let tag = tag_pattern();
+        Patterns {
+            markup: Regex::new(&format!(r"(?i){}|&(?:#\d+|#x[0-9a-f]+|[a-z][a-z0-9]+);", tag)).expect("valid regex"),
+            tag: Regex::new(&format!("(?i){}", tag)).expect("valid regex"),
+            cdata: Regex::new(r"(?s)<!\[CDATA\[(.*?)\]\]>").expect("valid regex"),
+            // "…[+2312 chars]" в NewsAPI, "[…]" и "[...]" в анонсах WordPress
+            truncation: Regex::new(r"(?:\.\.\.|…)?\s*\[(?:\+\d+\s*chars|…|\.\.\.)\]").expect("valid regex"),
+            feed_footer: Regex::new(r"(?i)\bThe post .{1,300}? appeared first on [^.\n]{1,100}\.?").expect("valid regex"),
+            url: Regex::new(r"(?i)\b(?:https?://|www\.)\S+").expect("valid regex"),
+            whitespace: Regex::new(r"\s+").expect("valid regex"),
+        }
+    })
+}
+
+/// Тег известного элемента с корректными атрибутами, комментарий или DOCTYPE
+fn tag_pattern() -> String {
+    let names: Vec<&str> = SKIPPED_TAGS.iter().chain(BLOCK_TAGS).chain(INLINE_TAGS).copied().collect();
+    let attribute = format!(
+        r#"(?:[a-z_:][-a-z0-9_:.]*\s*=\s*(?:"[^"]*"|'[^']*'|[^\s"'=<>`]+)|(?:{})\b)"#,
+        BOOLEAN_ATTRIBUTES.join("|")
+    );
+    format!(r"(?s:<!--.*?-->)|<!doctype[^>]*>|</?(?:{})(?:\s+{})*\s*/?>", names.join("|"), attribute)
+}
+
+/// Текст для модели: разметка и содержимое script/style удалены, HTML-сущности раскодированы,
+/// Unicode приведён к NFKC, типографские кавычки и тире — к ASCII, маркеры обрезки,
+/// подвалы RSS и ссылки удалены, пробелы схлопнуты
+pub fn clean_text(text: &str) -> String {
+    let patterns = patterns();
+
+    // CDATA в описаниях лент — обёртка текста, а не комментарий
+    let mut text = patterns.cdata.replace_all(text, "$1").into_owned();
+    for _ in 0..MAX_DECODE_PASSES {
+        if !patterns.markup.is_match(&text) {
+            break;
+        }
+        text = html_to_text(&escape_stray_brackets(&text, &patterns.tag));
+    }
+
+    let text: String = text.nfkc().filter_map(normalize_char).collect();
+    let text = patterns.truncation.replace_all(&text, " ");
+    let text = patterns.feed_footer.replace_all(&text, " ");
+    let text = patterns.url.replace_all(&text, " ");
+    let text = patterns.whitespace.replace_all(&text, " ");
+    text.trim().to_string()
+}
+
+/// Экранирует "<", с которого не начинается тег: парсер HTML принял бы за тег
+/// и удалил сравнение вроде "x<y and z>w"
+fn escape_stray_brackets(text: &str, tag: &Regex) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    let mut last = 0;
+    for found in tag.find_iter(text) {
+        escaped.push_str(&text[last..found.start()].replace('<', "&lt;"));
+        escaped.push_str(found.as_str());
+        last = found.end();
+    }
+    escaped.push_str(&text[last..].replace('<', "&lt;"));
+    escaped
+}
+
+/// Текстовые узлы HTML-фрагмента; сущности раскодирует парсер
+fn html_to_text(html: &str) -> String {
+    let fragment = Html::parse_fragment(html);
+    let mut text = String::with_capacity(html.len());
+    collect_text(*fragment.root_element(), &mut text);
+    text
+}
+
+fn collect_text(node: NodeRef<Node>, out: &mut String) {
+    match node.value() {
+        Node::Text(text) => out.push_str(text),
+        Node::Element(element) => {
+            let name = element.name();
+            if SKIPPED_TAGS.contains(&name) {
+                return;
+            }
+            let is_block = BLOCK_TAGS.contains(&name);
+            if is_block {
+                out.push(' ');
+            }
+            for child in node.children() {
+                collect_text(child, out);
+            }
+            if is_block {
+                out.push(' ');
+            }
+        }
+        Node::Document | Node::Fragment => {
+            for child in node.children() {
+                collect_text(child, out);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn normalize_char(c: char) -> Option<char> {
+    match c {
+        '\u{2018}' | '\u{2019}' | '\u{201A}' | '\u{201B}' | '\u{2032}' => Some('\''),
+        '\u{201C}' | '\u{201D}' | '\u{201E}' | '\u{201F}' | '\u{2033}' | '«' | '»' => Some('"'),
+        '\u{2010}'..='\u{2015}' | '\u{2212}' => Some('-'),
+        '\u{200B}'..='\u{200D}' | '\u{2060}' | '\u{FEFF}' | '\u{00AD}' => None,
+        c if c.is_control() && !c.is_whitespace() => Some(' '),
+        c => Some(c),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn newsapi_snippet_with_truncation_marker() {
+        assert_eq!(
+            clean_text("Bitcoin\u{2019}s rally didn\u{2019}t stall \u{2014} yet. Traders are watching $70,000\u{2026} [+2312 chars]"),
+            "Bitcoin's rally didn't stall - yet. Traders are watching $70,000"
+        );
+    }
+
+    #[test]
+    fn rss_description_with_entities_and_double_escaping() {
+        assert_eq!(
+            clean_text("<p>Spot bitcoin ETFs saw &lt;b&gt;$1.2B&lt;/b&gt; in inflows &amp;amp; BlackRock&#8217;s IBIT led.</p>"),
+            "Spot bitcoin ETFs saw $1.2B in inflows & BlackRock's IBIT led."
+        );
+        assert_eq!(clean_text("AT&amp;T &quot;hedges&quot; with BTC &ndash; report"), "AT&T \"hedges\" with BTC - report");
+    }
+
+    #[test]
+    fn nested_tags_script_footer_and_links_are_removed() {
+        let cointelegraph = r#"<p style="float:right;"><img src="https://s3.cointelegraph.com/uploads/2025-08/btc.jpg"></p>
+            <script type="text/javascript">window.dataLayer.push({"event": "bitcoin crash"});</script>
+            <style>.news{color:red}</style>
+            <p>Bitcoin miners sold&nbsp;fewer coins in&#x20;August.</p><p>Read more at https://cointelegraph.com/news/miners</p>
+            <p>The post <a href="https://cointelegraph.com/x">Bitcoin miners hold</a> appeared first on Cointelegraph.</p>"#;
+        assert_eq!(clean_text(cointelegraph), "Bitcoin miners sold fewer coins in August. Read more at");
+
+        assert_eq!(
+            clean_text("<ul><li><strong>ETF <em>inflows</em></strong> hit a record</li><li>Miners <span class=\"x\">hold</span></li></ul>"),
+            "ETF inflows hit a record Miners hold"
+        );
+        assert_eq!(clean_text("<!-- wp:paragraph -->Bitcoin<br/>rebounds<!-- /wp:paragraph -->"), "Bitcoin rebounds");
+    }
+
+    #[test]
+    fn cdata_content_is_kept() {
+        assert_eq!(clean_text("<![CDATA[Bitcoin tops $70K as <b>ETF</b> demand grows]]>"), "Bitcoin tops $70K as ETF demand grows");
+        assert_eq!(clean_text("Summary: <![CDATA[price <b and c> a]]>"), "Summary: price <b and c> a");
+    }
+
+    #[test]
+    fn inline_comparisons_are_not_markup() {
+        assert_eq!(clean_text("price <b and c> a"), "price <b and c> a");
+        assert_eq!(clean_text("x<y and z>w"), "x<y and z>w");
+        assert_eq!(clean_text("BTC<70k while ETH>3k"), "BTC<70k while ETH>3k");
+        // Сравнение рядом с настоящей разметкой тоже сохраняется
+        assert_eq!(clean_text("<p>If price <b and c> a, <em>sell</em></p>"), "If price <b and c> a, sell");
+        assert_eq!(clean_text("<p>fees &lt; 1% when x<y and z>w</p>"), "fees < 1% when x<y and z>w");
+    }
+
+    #[test]
+    fn wordpress_teaser_blocks_do_not_glue_words() {
+        assert_eq!(
+            clean_text("<div>Ｂｉｔｃｏｉｎ ETF</div><div>approval odds rise [&#8230;]</div>"),
+            "Bitcoin ETF approval odds rise"
+        );
+    }
+
+    #[test]
+    fn plain_text_is_only_normalized() {
+        assert_eq!(clean_text("  BTC < $70k\u{200B} and rising\n\n"), "BTC < $70k and rising");
+        assert_eq!(clean_text("Биткоин «вырос» на 5%"), "Биткоин \"вырос\" на 5%");
+        assert_eq!(clean_text(""), "");
+        assert_eq!(clean_text(" \u{FEFF}\u{00AD} "), "");
+    }
+}
@@ -0,0 +1,196 @@
+use std::collections::{BTreeMap, HashMap};
+use chrono::{DateTime, Duration, DurationRound, NaiveDate, Utc};
+
+use crate::config::SeriesGranularity;
+use crate::models::{BitcoinNews, BitcoinPrice, SentimentSeriesPoint, SentimentTimeSeries};
+
+// Предел числа точек ряда: 90 дней по часам. Более длинный почасовой ряд строится по дням,
+// а у дневного остаются последние точки
+pub const MAX_SERIES_POINTS: usize = 24 * 90;
+
+#[derive(Default)]
+struct Bucket {
+    positive: usize,
+    negative: usize,
+    neutral: usize,
+    score_sum: f64,
+}
+
+/// Временной ряд настроений, выровненный по ряду цен.
+/// Периоды без новостей сохраняются, чтобы ряд можно было строить на графике без пропусков.
+pub fn build_sentiment_series(
+    prices: &[BitcoinPrice],
+    news_items: &[BitcoinNews],
+    granularity: SeriesGranularity,
+) -> SentimentTimeSeries {
+    let granularity = match granularity {
+        SeriesGranularity::Hourly if span_hours(prices, news_items) > MAX_SERIES_POINTS as i64 => {
+            tracing::warn!("Период длиннее {} часов, ряд настроений строится по дням", MAX_SERIES_POINTS);
+            SeriesGranularity::Daily
+        }
+        granularity => granularity,
+    };
+    let step = match granularity {
+        SeriesGranularity::Daily => Duration::days(1),
+        SeriesGranularity::Hourly => Duration::hours(1),
+    };
+
+    let mut buckets: BTreeMap<DateTime<Utc>, Bucket> = BTreeMap::new();
+    for news in news_items {
+        let Some(period) = news.published_datetime().and_then(|dt| dt.duration_trunc(step).ok()) else {
+            continue;
+        };
+        let bucket = buckets.entry(period).or_default();
+        match news.is_positive {
+            Some(true) => bucket.positive += 1,
+            Some(false) => bucket.negative += 1,
+            None => bucket.neutral += 1,
+        }
+        bucket.score_sum += news.sentiment_value();
+    }
+
+    let close_prices: HashMap<NaiveDate, f64> = prices.iter().map(|p| (p.date, p.price)).collect();
+
+    // Диапазон ряда охватывает и цены, и новости
+    let day_start = |date: NaiveDate| date.and_hms_opt(0, 0, 0).map(|dt| dt.and_utc());
+    let first = prices.iter().filter_map(|p| day_start(p.date))
+        .chain(buckets.keys().next().copied())
+        .min();
+    let last = prices.iter().filter_map(|p| day_start(p.date).map(|dt| dt + Duration::days(1) - step))
+        .chain(buckets.keys().next_back().copied())
+        .max();
+
+    let mut points = Vec::new();
+    if let (Some(first), Some(last)) = (first, last) {
+        let mut period = first.max(last - step * (MAX_SERIES_POINTS as i32 - 1));
+        while period <= last {
+            let bucket = buckets.remove(&period).unwrap_or_default();
+            let article_count = bucket.positive + bucket.negative + bucket.neutral;
+
+            points.push(SentimentSeriesPoint {
+                period_start: period.to_rfc3339(),
+                article_count,
+                positive_count: bucket.positive,
+                negative_count: bucket.negative,
+                neutral_count: bucket.neutral,
+                mean_sentiment_score: (article_count > 0).then(|| bucket.score_sum / article_count as f64),
+                close_price: close_prices.get(&period.date_naive()).copied(),
+            });
+            period += step;
+        }
+    }
+
+    SentimentTimeSeries {
+        granularity: granularity.as_str().to_string(),
+        points,
+    }
+}
+
+/// Длительность периода цен и новостей в часах
+fn span_hours(prices: &[BitcoinPrice], news_items: &[BitcoinNews]) -> i64 {
+    let day_start = |date: NaiveDate| date.and_hms_opt(0, 0, 0).map(|dt| dt.and_utc());
+    let times: Vec<DateTime<Utc>> = prices.iter()
+        .filter_map(|p| day_start(p.date))
+        .chain(prices.iter().filter_map(|p| day_start(p.date).map(|dt| dt + Duration::days(1))))
+        .chain(news_items.iter().filter_map(|n| n.published_datetime()))
+        .collect();
+    match (times.iter().min(), times.iter().max()) {
+        (Some(first), Some(last)) => (*last - *first).num_hours(),
+        _ => 0,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prices(start: NaiveDate, count: u64) -> Vec<BitcoinPrice> {
+        (0..count)
+            .map(|i| BitcoinPrice { date: start + chrono::Days::new(i), price: 60000.0 + i as f64 * 1000.0 })
+            .collect()
+    }
+
+    fn news(published_at: &str, is_positive: Option<bool>) -> BitcoinNews {
+        BitcoinNews {
+            title: "Bitcoin".to_string(),
+            is_positive,
+            published_at: Some(published_at.to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn sample_news() -> Vec<BitcoinNews> {
+        vec![
+            news("2025-08-18T08:00:00Z", Some(true)),
+            news("2025-08-18T21:30:00+03:00", Some(false)),
+            news("2025-08-20T10:15:00Z", None),
+        ]
+    }
+
+    #[test]
+    fn daily_series_keeps_days_without_news() {
+        let prices = prices(NaiveDate::from_ymd_opt(2025, 8, 18).unwrap(), 3);
+        let daily = build_sentiment_series(&prices, &sample_news(), SeriesGranularity::Daily);
+
+        assert_eq!(daily.granularity, "daily");
+        assert_eq!(daily.points.len(), 3);
+        assert_eq!(daily.points[0].article_count, 2);
+        assert_eq!(daily.points[0].mean_sentiment_score, Some(0.0));
+        assert_eq!(daily.points[1].article_count, 0);
+        assert_eq!(daily.points[1].mean_sentiment_score, None);
+        assert_eq!(daily.points[1].close_price, Some(61000.0));
+        assert_eq!(daily.points[2].neutral_count, 1);
+    }
+
+    #[test]
+    fn hourly_series_buckets_by_utc_hour() {
+        let prices = prices(NaiveDate::from_ymd_opt(2025, 8, 18).unwrap(), 3);
+        let hourly = build_sentiment_series(&prices, &sample_news(), SeriesGranularity::Hourly);
+
+        assert_eq!(hourly.granularity, "hourly");
+        assert_eq!(hourly.points.len(), 72);
+        assert_eq!(hourly.points[8].positive_count, 1);
+        // 21:30 по Москве — 18:30 UTC
+        assert_eq!(hourly.points[18].negative_count, 1);
+        assert_eq!(hourly.points[18].close_price, Some(60000.0));
+    }
+
+    #[test]
+    fn empty_inputs_give_empty_series() {
+        let series = build_sentiment_series(&[], &[], SeriesGranularity::Hourly);
+        assert_eq!(series.granularity, "hourly");
+        assert!(series.points.is_empty());
+    }
+
+    #[test]
+    fn news_without_prices_span_only_their_periods() {
+        let mut undated = news("2025-08-18T08:00:00Z", Some(true));
+        undated.published_at = None;
+        let series = build_sentiment_series(&[], &[news("2025-08-18T08:00:00Z", Some(true)), undated], SeriesGranularity::Daily);
+        assert_eq!(series.points.len(), 1);
+        assert_eq!(series.points[0].article_count, 1);
+        assert_eq!(series.points[0].close_price, None);
+    }
+
+    #[test]
+    fn long_periods_fall_back_to_days_and_are_capped() {
+        let archive = prices(NaiveDate::from_ymd_opt(2020, 1, 1).unwrap(), 2000);
+
+        let long_hourly = build_sentiment_series(&archive, &[], SeriesGranularity::Hourly);
+        assert_eq!(long_hourly.granularity, "daily");
+        assert_eq!(long_hourly.points.len(), 2000);
+
+        let stray = news("1970-01-01T00:00:00Z", Some(true));
+        let long_daily = build_sentiment_series(&archive, &[stray], SeriesGranularity::Daily);
+        assert_eq!(long_daily.points.len(), MAX_SERIES_POINTS);
+        assert_eq!(long_daily.points.last().unwrap().close_price, Some(60000.0 + 1999.0 * 1000.0));
+    }
+
+    #[test]
+    fn exactly_ninety_days_stay_hourly() {
+        let prices = prices(NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(), 90);
+        let series = build_sentiment_series(&prices, &[], SeriesGranularity::Hourly);
+        assert_eq!(series.granularity, "hourly");
+        assert_eq!(series.points.len(), MAX_SERIES_POINTS);
+    }
+}
@@ -1,10 +1,948 @@
 use anyhow::Result;
 use config::Config;
+use std::collections::HashMap;
 use std::env;
 
+use crate::analytics::Query;
+
+/// Шаг временного ряда настроений в ответе анализа
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SeriesGranularity {
+    #[default]
+    Daily,
+    Hourly,
+}
+
+impl SeriesGranularity {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SeriesGranularity::Daily => "daily",
+            SeriesGranularity::Hourly => "hourly",
+        }
+    }
+}
+
+/// Веса и пороги `DataMakerDecisionService` (секция `[decision]`)
+#[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
+#[serde(default)]
+pub struct DecisionConfig {
+    pub price_weight: f64,
+    pub news_weight: f64,
+    pub strong_price_change_pct: f64,
+    pub moderate_price_change_pct: f64,
+    pub strong_sentiment_threshold: f64,
+    pub moderate_sentiment_threshold: f64,
+    pub min_news_for_confidence: usize,
+    pub min_significant_price_change_pct: f64,
+    pub low_daily_volatility_pct: f64,
+    pub low_max_drawdown_pct: f64,
+    pub key_news_count: usize,
+}
+
+impl Default for DecisionConfig {
+    fn default() -> Self {
+        DecisionConfig {
+            price_weight: 0.6,
+            news_weight: 0.4,
+            strong_price_change_pct: 5.0,
+            moderate_price_change_pct: 2.0,
+            strong_sentiment_threshold: 0.6,
+            moderate_sentiment_threshold: 0.2,
+            min_news_for_confidence: 3,
+            min_significant_price_change_pct: 1.0,
+            low_daily_volatility_pct: 3.0,
+            low_max_drawdown_pct: 10.0,
+            key_news_count: 5,
+        }
+    }
+}
+
+/// Переопределения параметров решения для отдельного запроса
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+pub struct DecisionOverrides {
+    pub price_weight: Option<f64>,
+    pub news_weight: Option<f64>,
+    pub strong_price_change_pct: Option<f64>,
+    pub moderate_price_change_pct: Option<f64>,
+    pub strong_sentiment_threshold: Option<f64>,
+    pub moderate_sentiment_threshold: Option<f64>,
+    pub min_news_for_confidence: Option<usize>,
+    pub min_significant_price_change_pct: Option<f64>,
+    pub low_daily_volatility_pct: Option<f64>,
+    pub low_max_drawdown_pct: Option<f64>,
+    pub key_news_count: Option<usize>,
+}
+
+impl DecisionConfig {
+    /// Применяет переопределения запроса поверх значений из конфигурации
+    pub fn with_overrides(&self, overrides: &DecisionOverrides) -> DecisionConfig {
+        DecisionConfig {
+            price_weight: overrides.price_weight.unwrap_or(self.price_weight),
+            news_weight: overrides.news_weight.unwrap_or(self.news_weight),
+            strong_price_change_pct: overrides.strong_price_change_pct.unwrap_or(self.strong_price_change_pct),
+            moderate_price_change_pct: overrides.moderate_price_change_pct.unwrap_or(self.moderate_price_change_pct),
+            strong_sentiment_threshold: overrides.strong_sentiment_threshold.unwrap_or(self.strong_sentiment_threshold),
+            moderate_sentiment_threshold: overrides.moderate_sentiment_threshold.unwrap_or(self.moderate_sentiment_threshold),
+            min_news_for_confidence: overrides.min_news_for_confidence.unwrap_or(self.min_news_for_confidence),
+            min_significant_price_change_pct: overrides.min_significant_price_change_pct.unwrap_or(self.min_significant_price_change_pct),
+            low_daily_volatility_pct: overrides.low_daily_volatility_pct.unwrap_or(self.low_daily_volatility_pct),
+            low_max_drawdown_pct: overrides.low_max_drawdown_pct.unwrap_or(self.low_max_drawdown_pct),
+            key_news_count: overrides.key_news_count.unwrap_or(self.key_news_count),
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        let weights = [self.price_weight, self.news_weight];
+        if weights.iter().any(|w| !(0.0..=1.0).contains(w)) {
+            return Err(anyhow::anyhow!("decision weights must be between 0 and 1"));
+        }
+        if self.price_weight + self.news_weight <= 0.0 {
+            return Err(anyhow::anyhow!("decision weights cannot both be zero"));
+        }
+
+        if !(self.moderate_price_change_pct > 0.0 && self.moderate_price_change_pct < self.strong_price_change_pct) {
+            return Err(anyhow::anyhow!("price change thresholds must satisfy 0 < moderate < strong"));
+        }
+
+        if !(self.moderate_sentiment_threshold > 0.0
+            && self.moderate_sentiment_threshold < self.strong_sentiment_threshold
+            && self.strong_sentiment_threshold < 1.0)
+        {
+            return Err(anyhow::anyhow!("sentiment thresholds must satisfy 0 < moderate < strong < 1"));
+        }
+
+        if self.min_significant_price_change_pct < 0.0
+            || self.low_daily_volatility_pct <= 0.0
+            || self.low_max_drawdown_pct <= 0.0
+        {
+            return Err(anyhow::anyhow!("confidence thresholds must be positive"));
+        }
+
+        if self.key_news_count == 0 || self.key_news_count > 50 {
+            return Err(anyhow::anyhow!("key_news_count must be between 1 and 50"));
+        }
+
+        Ok(())
+    }
+}
+
+/// Веса модели ранжирования ключевых новостей (секция `[ranking]`)
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct RankingConfig {
+    pub confidence_weight: f64,
+    pub relevance_weight: f64,
+    pub source_trust_weight: f64,
+    pub recency_weight: f64,
+    pub recurrence_weight: f64,
+    pub recency_half_life_hours: f64,
+    pub default_source_trust: f64,
+    pub source_trust: HashMap<String, f64>, // домен -> доверие 0.0..1.0
+}
+
+impl Default for RankingConfig {
+    fn default() -> Self {
+        RankingConfig {
+            confidence_weight: 0.3,
+            relevance_weight: 0.25,
+            source_trust_weight: 0.15,
+            recency_weight: 0.15,
+            recurrence_weight: 0.15,
+            recency_half_life_hours: 24.0,
+            default_source_trust: 0.5,
+            source_trust: HashMap::new(),
+        }
+    }
+}
+
+impl RankingConfig {
+    pub fn validate(&self) -> Result<()> {
+        let weights = [
+            self.confidence_weight,
+            self.relevance_weight,
+            self.source_trust_weight,
+            self.recency_weight,
+            self.recurrence_weight,
+        ];
+        if weights.iter().any(|w| *w < 0.0) || weights.iter().sum::<f64>() <= 0.0 {
+            return Err(anyhow::anyhow!("ranking weights must be non-negative and not all zero"));
+        }
+        if self.recency_half_life_hours <= 0.0 {
+            return Err(anyhow::anyhow!("recency_half_life_hours must be positive"));
+        }
+        let trusts = self.source_trust.values().chain(std::iter::once(&self.default_source_trust));
+        if trusts.into_iter().any(|t| !(0.0..=1.0).contains(t)) {
+            return Err(anyhow::anyhow!("source trust values must be between 0 and 1"));
+        }
+        Ok(())
+    }
+}
+
+/// Дедупликация и кластеризация новостей в сюжеты (секция `[dedup]`)
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct DedupConfig {
+    pub enabled: bool,
+    pub shingle_size: usize, // длина шингла в словах
+    pub num_hashes: usize, // размер MinHash-сигнатуры
+    pub near_duplicate_threshold: f64,
+    pub story_similarity_threshold: f64,
+    pub story_level_statistics: bool, // считать NewsStatistics по сюжетам, а не по статьям
+}
+
+impl Default for DedupConfig {
+    fn default() -> Self {
+        DedupConfig {
+            enabled: true,
+            shingle_size: 3,
+            num_hashes: 64,
+            near_duplicate_threshold: 0.8,
+            story_similarity_threshold: 0.4,
+            story_level_statistics: false,
+        }
+    }
+}
+
+impl DedupConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.shingle_size == 0 || self.shingle_size > 10 {
+            return Err(anyhow::anyhow!("shingle_size must be between 1 and 10"));
+        }
+        if self.num_hashes < 8 || self.num_hashes > 512 {
+            return Err(anyhow::anyhow!("num_hashes must be between 8 and 512"));
+        }
+        if !(self.story_similarity_threshold > 0.0
+            && self.story_similarity_threshold <= self.near_duplicate_threshold
+            && self.near_duplicate_threshold <= 1.0)
+        {
+            return Err(anyhow::anyhow!("dedup thresholds must satisfy 0 < story <= near_duplicate <= 1"));
+        }
+        Ok(())
+    }
+}
+
+/// Языки источников и маршрутизация анализа настроений по языку (секция `[languages]`)
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct LanguageConfig {
+    pub newsapi: Vec<String>, // отдельный запрос на каждый язык; пусто — без фильтра
+    pub rss: Vec<String>, // допустимые языки записей RSS; пусто — любые
+    pub huggingface_models: HashMap<String, String>, // язык -> URL модели; прочие идут в huggingface_api_url
+}
+
+impl Default for LanguageConfig {
+    fn default() -> Self {
+        LanguageConfig {
+            newsapi: vec!["en".to_string()],
+            rss: Vec::new(),
+            huggingface_models: HashMap::new(),
+        }
+    }
+}
+
+impl LanguageConfig {
+    pub fn validate(&self) -> Result<()> {
+        let codes = self.newsapi.iter()
+            .chain(&self.rss)
+            .chain(self.huggingface_models.keys());
+        for code in codes {
+            if code.len() != 2 || !code.chars().all(|c| c.is_ascii_lowercase()) {
+                return Err(anyhow::anyhow!("language '{}' must be a lowercase ISO 639-1 code", code));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Аспекты новостей (секция `[aspects]`): тема → ключевые слова, `*` — любой суффикс
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct AspectConfig {
+    pub enabled: bool,
+    pub taxonomy: HashMap<String, Vec<String>>,
+    pub zero_shot_model_url: Option<String>, // zero-shot классификатор HF для статей без ключевых слов
+    pub zero_shot_threshold: f64,
+}
+
+impl Default for AspectConfig {
+    fn default() -> Self {
+        let taxonomy = [
+            ("regulation", &["regulat*", "sec", "cftc", "lawsuit*", "ban", "bans", "banned", "sanction*", "compliance", "licens*", "mica", "court*", "регулир*", "регулятор*", "запрет*", "закон*", "суд*"][..]),
+            ("adoption", &["adopt*", "payment*", "merchant*", "legal tender", "corporate treasury", "accept*", "integrat*", "partnership*", "внедр*", "платеж*", "партнерств*"][..]),
+            ("security", &["hack*", "exploit*", "breach*", "stolen", "theft", "scam*", "phishing", "vulnerab*", "attack*", "взлом*", "краж*", "мошенни*", "уязвим*", "атак*"][..]),
+            ("macro", &["inflation", "fed", "federal reserve", "interest rate*", "rate cut*", "rate hike*", "cpi", "recession", "dollar", "treasury yield*", "инфляц*", "фрс", "ставк*", "рецесси*", "доллар*"][..]),
+            ("etf", &["etf*", "blackrock", "grayscale", "gbtc", "ibit", "fidelity", "inflow*", "outflow*", "биржев* фонд*"][..]),
+        ];
+        AspectConfig {
+            enabled: true,
+            taxonomy: taxonomy.iter()
+                .map(|(aspect, keywords)| (aspect.to_string(), keywords.iter().map(|k| k.to_string()).collect()))
+                .collect(),
+            zero_shot_model_url: None,
+            zero_shot_threshold: 0.5,
+        }
+    }
+}
+
+impl AspectConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.enabled && self.taxonomy.is_empty() && self.zero_shot_model_url.is_none() {
+            return Err(anyhow::anyhow!("aspects.taxonomy cannot be empty when aspects are enabled"));
+        }
+        if let Some((aspect, _)) = self.taxonomy.iter().find(|(_, keywords)| keywords.is_empty()) {
+            return Err(anyhow::anyhow!("aspect '{}' has no keywords", aspect));
+        }
+        if !(self.zero_shot_threshold > 0.0 && self.zero_shot_threshold < 1.0) {
+            return Err(anyhow::anyhow!("aspects.zero_shot_threshold must be between 0 and 1"));
+        }
+        Ok(())
+    }
+}
+
+/// Извлечение сущностей (секция `[entities]`). Записи справочника и шаблоны
+/// дополняют встроенные; запись с тем же именем заменяет встроенную
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct EntityConfig {
+    pub enabled: bool,
+    pub top_n: usize, // сущностей в AnalysisResult.top_entities
+    pub gazetteer: Vec<EntityDefinition>,
+    pub patterns: Vec<EntityPattern>,
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct EntityDefinition {
+    pub name: String,
+    pub kind: String,
+    #[serde(default)]
+    pub aliases: Vec<String>, // варианты написания; аббревиатуры в верхнем регистре — с учётом регистра
+}
+
+#[derive(Clone, Debug, serde::Deserialize)]
+pub struct EntityPattern {
+    pub kind: String,
+    pub regex: String, // первая группа захвата — имя сущности
+}
+
+impl Default for EntityConfig {
+    fn default() -> Self {
+        EntityConfig {
+            enabled: true,
+            top_n: 10,
+            gazetteer: Vec::new(),
+            patterns: Vec::new(),
+        }
+    }
+}
+
+impl EntityConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.top_n == 0 || self.top_n > 100 {
+            return Err(anyhow::anyhow!("entities.top_n must be between 1 and 100"));
+        }
+        if let Some(entry) = self.gazetteer.iter().find(|e| e.name.trim().is_empty() || e.kind.trim().is_empty()) {
+            return Err(anyhow::anyhow!("entity '{}' must have a name and a kind", entry.name));
+        }
+        for pattern in &self.patterns {
+            regex::Regex::new(&pattern.regex)
+                .map_err(|e| anyhow::anyhow!("invalid entity pattern '{}': {}", pattern.regex, e))?;
+        }
+        Ok(())
+    }
+}
+
+/// Загрузка полного текста статей по ссылкам (секция `[article_fetch]`)
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct ArticleFetchConfig {
+    pub enabled: bool,
+    pub max_concurrent: usize,
+    pub timeout_secs: u64,
+    pub max_bytes: usize,               // предел размера загружаемой страницы
+    pub min_text_chars: usize,          // более короткий результат считается неудачным извлечением
+    pub max_text_chars: usize,          // длиннее — обрезается
+    pub snippet_threshold_chars: usize, // фрагменты короче загружаются полностью
+    pub user_agent: String,
+}
+
+impl Default for ArticleFetchConfig {
+    fn default() -> Self {
+        ArticleFetchConfig {
+            enabled: false,
+            max_concurrent: 4,
+            timeout_secs: 10,
+            max_bytes: 2 * 1024 * 1024,
+            min_text_chars: 300,
+            max_text_chars: 20_000,
+            snippet_threshold_chars: 1000,
+            user_agent: format!("btc_news_analyzer/{}", env!("CARGO_PKG_VERSION")),
+        }
+    }
+}
+
+impl ArticleFetchConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.max_concurrent == 0 || self.max_concurrent > 50 {
+            return Err(anyhow::anyhow!("article_fetch.max_concurrent must be between 1 and 50"));
+        }
+        if self.timeout_secs == 0 || self.max_bytes == 0 {
+            return Err(anyhow::anyhow!("article_fetch timeout and max_bytes must be positive"));
+        }
+        if self.min_text_chars > self.max_text_chars {
+            return Err(anyhow::anyhow!("article_fetch.min_text_chars cannot exceed max_text_chars"));
+        }
+        Ok(())
+    }
+}
+
+/// Постраничная загрузка NewsAPI и ограничения тарифа (секция `[newsapi]`)
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct NewsApiConfig {
+    pub page_size: usize,           // не больше 100
+    pub max_pages: usize,           // страниц на один язык
+    pub max_results: usize,         // тариф Developer отдаёт не больше 100 результатов на запрос
+    pub max_history_days: u32,      // глубина архива тарифа (Developer — месяц); 0 — без ограничения
+    pub daily_request_limit: usize, // запросов в сутки (Developer — 100); 0 — без ограничения
+}
+
+impl Default for NewsApiConfig {
+    fn default() -> Self {
+        NewsApiConfig {
+            page_size: 100,
+            max_pages: 5,
+            max_results: 100,
+            max_history_days: 30,
+            daily_request_limit: 100,
+        }
+    }
+}
+
+impl NewsApiConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.page_size == 0 || self.page_size > 100 {
+            return Err(anyhow::anyhow!("newsapi.page_size must be between 1 and 100"));
+        }
+        if self.max_pages == 0 || self.max_results == 0 {
+            return Err(anyhow::anyhow!("newsapi.max_pages and max_results must be positive"));
+        }
+        Ok(())
+    }
+}
+
+/// Базовые адреса API цен и RSS-ленты (секция `[endpoints]`), например для зеркала или прокси
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct EndpointsConfig {
+    pub coingecko_url: String,
+    pub binance_url: String,
+    pub coincap_url: String,
+    pub rss_feeds: Vec<String>,
+}
+
+impl Default for EndpointsConfig {
+    fn default() -> Self {
+        EndpointsConfig {
+            coingecko_url: "https://api.coingecko.com/api/v3".to_string(),
+            binance_url: "https://api.binance.com/api/v3".to_string(),
+            coincap_url: "https://api.coincap.io/v2".to_string(),
+            rss_feeds: vec![
+                "https://cointelegraph.com/rss".to_string(),
+                "https://coindesk.com/arc/outboundfeeds/rss/".to_string(),
+                "https://decrypt.co/feed".to_string(),
+            ],
+        }
+    }
+}
+
+impl EndpointsConfig {
+    pub fn validate(&self, news_sources: &NewsSourcesConfig) -> Result<()> {
+        let urls = [&self.coingecko_url, &self.binance_url, &self.coincap_url];
+        for url in urls.into_iter().chain(&self.rss_feeds) {
+            if !url.starts_with("http://") && !url.starts_with("https://") {
+                return Err(anyhow::anyhow!("endpoints URL '{}' must start with http:// or https://", url));
+            }
+        }
+        if self.rss_feeds.is_empty() && news_sources.order.iter().any(|s| s == "rss") {
+            return Err(anyhow::anyhow!("endpoints.rss_feeds cannot be empty when news_sources.order contains rss"));
+        }
+        Ok(())
+    }
+}
+
+// Встроенные редакционные источники; остальные имена в `[news_sources] order` — секции `[[json_sources]]`
+const BUILTIN_NEWS_SOURCES: &[&str] = &["newsapi", "rss", "gdelt", "files"];
+
+/// Порядок редакционных источников новостей (секция `[news_sources]`)
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct NewsSourcesConfig {
+    pub order: Vec<String>, // "newsapi", "rss", "gdelt", "files" или имя из [[json_sources]]
+    pub combine: bool,      // false — первый источник, вернувший новости; true — все по порядку
+}
+
+impl Default for NewsSourcesConfig {
+    fn default() -> Self {
+        NewsSourcesConfig {
+            order: vec!["newsapi".to_string(), "rss".to_string()],
+            combine: false,
+        }
+    }
+}
+
+impl NewsSourcesConfig {
+    pub fn validate(&self, json_sources: &[JsonSourceConfig]) -> Result<()> {
+        if self.order.is_empty() {
+            return Err(anyhow::anyhow!("news_sources.order cannot be empty"));
+        }
+        for name in &self.order {
+            if !BUILTIN_NEWS_SOURCES.contains(&name.as_str()) && !json_sources.iter().any(|s| &s.name == name) {
+                return Err(anyhow::anyhow!("news_sources.order: unknown source '{}'", name));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Новости и цены из локальных файлов для анализа без сети (секция `[files]`)
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct FileSourcesConfig {
+    pub news_directory: Option<String>, // JSONL, JSON, CSV, RSS и Atom; источник "files" в news_sources.order
+    pub recursive: bool,
+    pub prices_csv: Option<String>,      // CSV OHLC; если задан, цены берутся только из него
+    pub end_date: Option<chrono::NaiveDate>, // конец периода анализа; по умолчанию — последняя дата в данных
+    pub max_file_bytes: u64,
+}
+
+impl Default for FileSourcesConfig {
+    fn default() -> Self {
+        FileSourcesConfig {
+            news_directory: None,
+            recursive: false,
+            prices_csv: None,
+            end_date: None,
+            max_file_bytes: 64 * 1024 * 1024,
+        }
+    }
+}
+
+impl FileSourcesConfig {
+    pub fn validate(&self, news_sources: &NewsSourcesConfig) -> Result<()> {
+        if news_sources.order.iter().any(|s| s == "files") && self.news_directory.as_deref().is_none_or(str::is_empty) {
+            return Err(anyhow::anyhow!("files.news_directory is required when news_sources.order contains files"));
+        }
+        if self.prices_csv.as_deref().is_some_and(str::is_empty) {
+            return Err(anyhow::anyhow!("files.prices_csv cannot be empty"));
+        }
+        if self.max_file_bytes == 0 {
+            return Err(anyhow::anyhow!("files.max_file_bytes must be positive"));
+        }
+        Ok(())
+    }
+}
+
+/// GDELT DOC 2.0 API — бесплатный источник без ключа (секция `[gdelt]`)
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct GdeltConfig {
+    pub base_url: String,
+    pub max_records: usize, // не больше 250
+    pub max_history_days: u32, // DOC API хранит около трёх месяцев
+    pub fetch_tone: bool,   // дополнительный запрос ToneChart для тона статей
+    pub timeout_secs: u64,
+}
+
+impl Default for GdeltConfig {
+    fn default() -> Self {
+        GdeltConfig {
+            base_url: "https://api.gdeltproject.org/api/v2/doc/doc".to_string(),
+            max_records: 100,
+            max_history_days: 90,
+            fetch_tone: true,
+            timeout_secs: 20,
+        }
+    }
+}
+
+impl GdeltConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.max_records == 0 || self.max_records > 250 {
+            return Err(anyhow::anyhow!("gdelt.max_records must be between 1 and 250"));
+        }
+        if self.max_history_days == 0 || self.timeout_secs == 0 {
+            return Err(anyhow::anyhow!("gdelt max_history_days and timeout_secs must be positive"));
+        }
+        Ok(())
+    }
+}
+
+/// Произвольный JSON API новостей (секции `[[json_sources]]`). Пути к полям — через точку,
+/// индексы массивов — числами: "data.items", "source.name", "media.0.url"
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct JsonSourceConfig {
+    pub name: String,
+    pub url: String,        // подстановки {query}, {from} (YYYY-MM-DD), {days}
+    pub headers: HashMap<String, String>,
+    pub items_path: String, // пусто — корень ответа является массивом
+    pub title_path: String,
+    pub content_path: Option<String>,
+    pub url_path: Option<String>,
+    pub published_path: Option<String>, // RFC 3339, "YYYYMMDDTHHMMSSZ" или unix-время
+    pub source_path: Option<String>,
+    pub tone_path: Option<String>,
+    pub max_items: usize,
+    pub timeout_secs: u64,
+}
+
+impl Default for JsonSourceConfig {
+    fn default() -> Self {
+        JsonSourceConfig {
+            name: String::new(),
+            url: String::new(),
+            headers: HashMap::new(),
+            items_path: String::new(),
+            title_path: "title".to_string(),
+            content_path: None,
+            url_path: Some("url".to_string()),
+            published_path: None,
+            source_path: None,
+            tone_path: None,
+            max_items: 100,
+            timeout_secs: 10,
+        }
+    }
+}
+
+impl JsonSourceConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.name.trim().is_empty() || BUILTIN_NEWS_SOURCES.contains(&self.name.as_str()) || self.name == "reddit" {
+            return Err(anyhow::anyhow!("json_sources name '{}' must be non-empty and not a built-in source", self.name));
+        }
+        if self.url.trim().is_empty() || self.title_path.trim().is_empty() {
+            return Err(anyhow::anyhow!("json_sources '{}' requires url and title_path", self.name));
+        }
+        if self.max_items == 0 || self.timeout_secs == 0 {
+            return Err(anyhow::anyhow!("json_sources '{}' max_items and timeout_secs must be positive", self.name));
+        }
+        Ok(())
+    }
+}
+
+/// Посты Reddit как социальный источник (секция `[reddit]`)
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct RedditConfig {
+    pub enabled: bool,
+    pub base_url: String,
+    pub subreddits: Vec<String>,
+    pub unfiltered_subreddits: Vec<String>, // все посты по теме, поисковый запрос не применяется
+    pub listing: String,                    // "new", "hot" или "top"
+    pub limit: usize,                       // постов на сабреддит, не больше 100
+    pub min_score: i64,
+    pub min_comments: u64,
+    pub timeout_secs: u64,
+    pub user_agent: String,                 // Reddit отклоняет запросы без собственного User-Agent
+}
+
+impl Default for RedditConfig {
+    fn default() -> Self {
+        RedditConfig {
+            enabled: false,
+            base_url: "https://www.reddit.com".to_string(),
+            subreddits: vec!["Bitcoin".to_string(), "CryptoCurrency".to_string()],
+            unfiltered_subreddits: vec!["Bitcoin".to_string()],
+            listing: "new".to_string(),
+            limit: 50,
+            min_score: 10,
+            min_comments: 5,
+            timeout_secs: 10,
+            user_agent: format!("btc_news_analyzer/{}", env!("CARGO_PKG_VERSION")),
+        }
+    }
+}
+
+impl RedditConfig {
+    pub fn validate(&self) -> Result<()> {
+        if !["new", "hot", "top"].contains(&self.listing.as_str()) {
+            return Err(anyhow::anyhow!("reddit.listing must be one of new, hot, top"));
+        }
+        if self.limit == 0 || self.limit > 100 {
+            return Err(anyhow::anyhow!("reddit.limit must be between 1 and 100"));
+        }
+        if self.enabled && self.subreddits.is_empty() {
+            return Err(anyhow::anyhow!("reddit.subreddits cannot be empty when reddit is enabled"));
+        }
+        if self.timeout_secs == 0 || self.user_agent.trim().is_empty() {
+            return Err(anyhow::anyhow!("reddit timeout and user_agent must be set"));
+        }
+        Ok(())
+    }
+}
+
+/// Оценка релевантности новостей (секция `[relevance]`)
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct RelevanceConfig {
+    pub enabled: bool,
+    pub min_relevance: f64,      // новости ниже порога отбрасываются до анализа настроений
+    pub weight_sentiment: bool,  // взвешивать общий sentiment_score по релевантности
+    pub title_weight: f64,
+    pub density_weight: f64,
+    pub position_weight: f64,
+    pub entity_weight: f64,
+    pub target_density: f64,     // совпадений на 100 слов, дающих максимальную плотность
+    pub entity_kinds: Vec<String>, // типы сущностей, указывающие на тему запроса
+}
+
+impl Default for RelevanceConfig {
+    fn default() -> Self {
+        RelevanceConfig {
+            enabled: true,
+            min_relevance: 0.0,
+            weight_sentiment: false,
+            title_weight: 0.35,
+            density_weight: 0.3,
+            position_weight: 0.15,
+            entity_weight: 0.2,
+            target_density: 5.0,
+            entity_kinds: vec!["etf".to_string(), "exchange".to_string()],
+        }
+    }
+}
+
+impl RelevanceConfig {
+    pub fn validate(&self) -> Result<()> {
+        if !(0.0..=1.0).contains(&self.min_relevance) {
+            return Err(anyhow::anyhow!("relevance.min_relevance must be between 0 and 1"));
+        }
+        let weights = [self.title_weight, self.density_weight, self.position_weight, self.entity_weight];
+        if weights.iter().any(|w| *w < 0.0) || weights.iter().sum::<f64>() <= 0.0 {
+            return Err(anyhow::anyhow!("relevance weights must be non-negative and not all zero"));
+        }
+        if self.target_density <= 0.0 {
+            return Err(anyhow::anyhow!("relevance.target_density must be positive"));
+        }
+        if self.min_relevance > 0.0 && !self.enabled {
+            return Err(anyhow::anyhow!("relevance.min_relevance requires relevance.enabled"));
+        }
+        Ok(())
+    }
+}
+
+// Предел длины параметра q в NewsAPI
+const NEWSAPI_MAX_QUERY_CHARS: usize = 500;
+
+/// Поисковые запросы (секция `[query]`): фразы в кавычках, AND/OR/NOT, скобки, исключения "-слово".
+/// Без запроса используется `bitcoin_keywords` через OR
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct QueryConfig {
+    pub default: Option<String>,
+    pub sources: HashMap<String, String>, // источник ("newsapi", "rss", "gdelt", "files", "reddit", имя JSON-источника) -> запрос
+}
+
+impl QueryConfig {
+    /// Общий запрос или список ключевых слов
+    pub fn query(&self, keywords: &[String]) -> crate::errors::Result<Query> {
+        match &self.default {
+            Some(query) => Query::parse(query),
+            None => Query::any_of(keywords),
+        }
+    }
+
+    /// Запрос для источника: переопределение, общий запрос или список ключевых слов
+    pub fn for_source(&self, source: &str, keywords: &[String]) -> crate::errors::Result<Query> {
+        match self.sources.get(source) {
+            Some(query) => Query::parse(query),
+            None => self.query(keywords),
+        }
+    }
+
+    /// Все запросы разбираются; синтаксис NewsAPI и GDELT проверяется, если источник в `order`
+    pub fn validate(&self, keywords: &[String], news_sources: &NewsSourcesConfig) -> Result<()> {
+        let enabled = |name: &str| news_sources.order.iter().any(|source| source == name);
+        for source in self.sources.keys().map(String::as_str).chain(["newsapi", "rss", "gdelt", "reddit"]) {
+            let query = self.for_source(source, keywords)
+                .and_then(|query| match source {
+                    "newsapi" if enabled(source) => query.validate_newsapi().map(|_| query),
+                    "gdelt" if enabled(source) => query.validate_gdelt().map(|_| query),
+                    _ => Ok(query),
+                })
+                .map_err(|e| anyhow::anyhow!("query for source {}: {}", source, e))?;
+            if source == "newsapi" && query.to_newsapi().chars().count() > NEWSAPI_MAX_QUERY_CHARS {
+                return Err(anyhow::anyhow!("query for newsapi exceeds {} characters", NEWSAPI_MAX_QUERY_CHARS));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Движок анализа настроений
+#[derive(Clone, Copy, Debug, Default, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SentimentEngine {
+    /// HuggingFace, если задан ключ, иначе словарный анализ
+    #[default]
+    Auto,
+    HuggingFace,
+    Lexicon,
+    /// Локальная ONNX-модель (требует сборки с feature `onnx`)
+    Onnx,
+}
+
+/// Настройки анализа настроений (секция `[sentiment]`)
+#[derive(Clone, Debug, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct SentimentConfig {
+    pub engine: SentimentEngine,
+    pub lexicon_dir: Option<String>, // каталог с файлами <язык>.tsv поверх встроенных словарей
+    pub huggingface: HuggingFaceConfig,
+    pub onnx: OnnxConfig,
+    pub chunking: ChunkingConfig,
+}
+
+/// Разбиение длинных текстов на окна по лимиту токенов модели (секция `[sentiment.chunking]`)
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct ChunkingConfig {
+    pub max_tokens: usize,     // лимит модели вместе со служебными токенами
+    pub overlap_tokens: usize, // перекрытие соседних окон
+    pub max_windows: usize,    // окон на статью, остаток отбрасывается
+}
+
+impl Default for ChunkingConfig {
+    fn default() -> Self {
+        ChunkingConfig {
+            max_tokens: 512,
+            overlap_tokens: 64,
+            max_windows: 8,
+        }
+    }
+}
+
+impl ChunkingConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.max_tokens < 16 || self.max_tokens > 4096 {
+            return Err(anyhow::anyhow!("sentiment.chunking.max_tokens must be between 16 and 4096"));
+        }
+        if self.overlap_tokens * 2 > self.max_tokens {
+            return Err(anyhow::anyhow!("sentiment.chunking.overlap_tokens must be at most half of max_tokens"));
+        }
+        if self.max_windows == 0 {
+            return Err(anyhow::anyhow!("sentiment.chunking.max_windows must be positive"));
+        }
+        Ok(())
+    }
+}
+
+/// Параметры запросов к HuggingFace Inference API (секция `[sentiment.huggingface]`)
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct HuggingFaceConfig {
+    pub batch_size: usize,    // статей в одном запросе
+    pub max_retries: u32,     // повторов при 503, пока модель загружается
+    pub max_wait_secs: u64,   // верхняя граница ожидания по estimated_time
+    pub timeout_secs: u64,
+}
+
+impl Default for HuggingFaceConfig {
+    fn default() -> Self {
+        HuggingFaceConfig {
+            batch_size: 16,
+            max_retries: 3,
+            max_wait_secs: 60,
+            timeout_secs: 30,
+        }
+    }
+}
+
+impl HuggingFaceConfig {
+    pub fn validate(&self) -> Result<()> {
+        if self.batch_size == 0 || self.batch_size > 256 {
+            return Err(anyhow::anyhow!("sentiment.huggingface.batch_size must be between 1 and 256"));
+        }
+        if self.max_retries > 10 {
+            return Err(anyhow::anyhow!("sentiment.huggingface.max_retries must be at most 10"));
+        }
+        if self.max_wait_secs == 0 || self.timeout_secs == 0 {
+            return Err(anyhow::anyhow!("sentiment.huggingface wait and timeout must be positive"));
+        }
+        Ok(())
+    }
+}
+
+/// Настройки локальной ONNX-модели (секция `[sentiment.onnx]`)
+#[derive(Clone, Debug, serde::Deserialize)]
+#[serde(default)]
+pub struct OnnxConfig {
+    pub model_path: Option<String>,     // model.onnx (например, экспорт cardiffnlp/twitter-roberta-base-sentiment-latest)
+    pub tokenizer_path: Option<String>, // tokenizer.json; по умолчанию рядом с моделью
+    pub labels: Vec<String>,            // метки классов в порядке выходов модели
+    pub intra_threads: usize,
+}
+
+impl Default for OnnxConfig {
+    fn default() -> Self {
+        OnnxConfig {
+            model_path: None,
+            tokenizer_path: None,
+            labels: vec!["negative".to_string(), "neutral".to_string(), "positive".to_string()],
+            intra_threads: 1,
+        }
+    }
+}
+
+impl OnnxConfig {
+    /// Путь к tokenizer.json: явный или в каталоге модели
+    pub fn tokenizer_path(&self) -> Option<std::path::PathBuf> {
+        if let Some(path) = &self.tokenizer_path {
+            return Some(path.into());
+        }
+        let model_path = std::path::Path::new(self.model_path.as_ref()?);
+        Some(model_path.with_file_name("tokenizer.json"))
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if self.model_path.as_deref().is_none_or(str::is_empty) {
+            return Err(anyhow::anyhow!("sentiment.onnx.model_path is required when sentiment engine is onnx"));
+        }
+        if self.labels.len() < 2 {
+            return Err(anyhow::anyhow!("sentiment.onnx.labels must contain at least two labels"));
+        }
+        if self.intra_threads == 0 {
+            return Err(anyhow::anyhow!("sentiment.onnx.intra_threads must be positive"));
+        }
+        Ok(())
+    }
+}
+
+impl SentimentConfig {
+    pub fn uses_huggingface(&self, api_key: &str) -> bool {
+        match self.engine {
+            SentimentEngine::Auto => !api_key.is_empty(),
+            SentimentEngine::HuggingFace => true,
+            SentimentEngine::Lexicon | SentimentEngine::Onnx => false,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        self.huggingface.validate()?;
+        self.chunking.validate()?;
+        if self.engine == SentimentEngine::Onnx {
+            if !cfg!(feature = "onnx") {
+                return Err(anyhow::anyhow!("sentiment engine onnx requires building with `--features onnx`"));
+            }
+            self.onnx.validate()?;
+        }
+        Ok(())
+    }
+}
+
 #[derive(Clone, serde::Deserialize)]
 pub struct AppConfig {
-    pub coindesk_api_url: String,
     pub newsapi_url: String,
     pub newsapi_key: String,
     pub huggingface_api_url: String,
@@ -12,6 +950,41 @@ pub struct AppConfig {
     pub bitcoin_keywords: Vec<String>,
     pub max_articles: Option<usize>,
     pub max_concurrent_requests: Option<usize>,
+    pub sentiment_series_granularity: Option<SeriesGranularity>,
+    #[serde(default)]
+    pub decision: DecisionConfig,
+    #[serde(default)]
+    pub ranking: RankingConfig,
+    #[serde(default)]
+    pub dedup: DedupConfig,
+    #[serde(default)]
+    pub languages: LanguageConfig,
+    #[serde(default)]
+    pub sentiment: SentimentConfig,
+    #[serde(default)]
+    pub aspects: AspectConfig,
+    #[serde(default)]
+    pub entities: EntityConfig,
+    #[serde(default)]
+    pub article_fetch: ArticleFetchConfig,
+    #[serde(default)]
+    pub query: QueryConfig,
+    #[serde(default)]
+    pub relevance: RelevanceConfig,
+    #[serde(default)]
+    pub newsapi: NewsApiConfig,
+    #[serde(default)]
+    pub reddit: RedditConfig,
+    #[serde(default)]
+    pub news_sources: NewsSourcesConfig,
+    #[serde(default)]
+    pub gdelt: GdeltConfig,
+    #[serde(default)]
+    pub json_sources: Vec<JsonSourceConfig>,
+    #[serde(default)]
+    pub files: FileSourcesConfig,
+    #[serde(default)]
+    pub endpoints: EndpointsConfig,
 }
 
 impl AppConfig {
@@ -32,6 +1005,37 @@ impl AppConfig {
                 return Err(anyhow::anyhow!("max_concurrent_requests must be between 1 and 50"));
             }
         }
+
+        self.decision.validate()?;
+        self.ranking.validate()?;
+        self.dedup.validate()?;
+        self.languages.validate()?;
+        self.sentiment.validate()?;
+        self.aspects.validate()?;
+        self.entities.validate()?;
+        self.article_fetch.validate()?;
+        self.query.validate(&self.bitcoin_keywords, &self.news_sources)?;
+        self.relevance.validate()?;
+        self.newsapi.validate()?;
+        self.reddit.validate()?;
+        self.news_sources.validate(&self.json_sources)?;
+        self.gdelt.validate()?;
+        self.files.validate(&self.news_sources)?;
+        self.endpoints.validate(&self.news_sources)?;
+        for (index, source) in self.json_sources.iter().enumerate() {
+            source.validate()?;
+            if self.json_sources[..index].iter().any(|other| other.name == source.name) {
+                return Err(anyhow::anyhow!("json_sources name '{}' is duplicated", source.name));
+            }
+        }
+
+        if self.news_sources.order.iter().any(|s| s == "newsapi") && self.newsapi_key.is_empty() {
+            return Err(anyhow::anyhow!("NEWSAPI_KEY is required when news_sources.order contains newsapi"));
+        }
+
+        if self.sentiment.engine == SentimentEngine::HuggingFace && self.huggingface_api_key.is_empty() {
+            return Err(anyhow::anyhow!("HUGGINGFACE_API_KEY is required when sentiment engine is huggingface"));
+        }
         
         Ok(())
     }
@@ -48,13 +1052,62 @@ pub fn load_config() -> Result<AppConfig> {
 
     let mut config: AppConfig = settings.try_deserialize()?;
 
-    config.newsapi_key = env::var("NEWSAPI_KEY")
-        .map_err(|_| anyhow::anyhow!("NEWSAPI_KEY environment variable is required"))?;
+    // Ключ NewsAPI нужен, только если NewsAPI есть в news_sources.order (проверяется в validate)
+    config.newsapi_key = env::var("NEWSAPI_KEY").unwrap_or_default();
     
-    config.huggingface_api_key = env::var("HUGGINGFACE_API_KEY")
-        .map_err(|_| anyhow::anyhow!("HUGGINGFACE_API_KEY environment variable is required"))?;
+    // Без ключа HuggingFace используется словарный анализ (engine = "auto")
+    config.huggingface_api_key = env::var("HUGGINGFACE_API_KEY").unwrap_or_default();
 
     config.validate()?;
     
     Ok(config)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keywords() -> Vec<String> {
+        vec!["bitcoin".to_string(), "digital gold".to_string()]
+    }
+
+    fn order(sources: &[&str]) -> NewsSourcesConfig {
+        NewsSourcesConfig { order: sources.iter().map(|s| s.to_string()).collect(), combine: false }
+    }
+
+    #[test]
+    fn query_falls_back_to_keywords_and_honours_source_overrides() {
+        let config = QueryConfig {
+            default: None,
+            sources: HashMap::from([("rss".to_string(), "bitcoin -ethereum".to_string())]),
+        };
+        assert_eq!(config.for_source("newsapi", &keywords()).unwrap().to_newsapi(), r#"bitcoin OR "digital gold""#);
+        assert_eq!(config.for_source("rss", &keywords()).unwrap().to_newsapi(), "bitcoin NOT ethereum");
+        assert!(config.validate(&keywords(), &NewsSourcesConfig::default()).is_ok());
+    }
+
+    #[test]
+    fn malformed_query_or_blank_keywords_fail_validation() {
+        let invalid = QueryConfig { default: Some("(bitcoin".to_string()), ..Default::default() };
+        assert!(invalid.validate(&keywords(), &NewsSourcesConfig::default()).is_err());
+
+        let blank = vec![" ".to_string(), String::new()];
+        assert!(QueryConfig::default().validate(&blank, &NewsSourcesConfig::default()).is_err());
+    }
+
+    #[test]
+    fn source_syntax_is_checked_only_for_enabled_sources() {
+        let nested = QueryConfig { default: Some("bitcoin OR (btc etf)".to_string()), ..Default::default() };
+        assert!(nested.validate(&keywords(), &order(&["newsapi", "rss"])).is_ok());
+        let error = nested.validate(&keywords(), &order(&["newsapi", "gdelt"])).unwrap_err().to_string();
+        assert!(error.contains("query for source gdelt"), "{}", error);
+
+        let negated = QueryConfig {
+            default: None,
+            sources: HashMap::from([("newsapi".to_string(), "bitcoin OR -scam".to_string())]),
+        };
+        assert!(negated.validate(&keywords(), &order(&["rss"])).is_ok());
+        let error = negated.validate(&keywords(), &order(&["newsapi"])).unwrap_err().to_string();
+        assert!(error.contains("query for source newsapi"), "{}", error);
+    }
+}
@@ -19,6 +19,12 @@ pub enum BitcoinAnalysisError {
     #[error("Ошибка regex: {0}")]
     RegexError(#[from] regex::Error),
 
+    #[error("Ошибка чтения файла: {0}")]
+    IoError(#[from] std::io::Error),
+
+    #[error("Ошибка чтения CSV: {0}")]
+    CsvError(#[from] csv::Error),
+
     #[error("Ошибка выполнения задачи: {0}")]
     TaskError(#[from] JoinError),
 
@@ -33,6 +39,15 @@ pub enum BitcoinAnalysisError {
 
     #[error("Нет доступных источников данных: {0}")]
     NoDataSourcesAvailable(String),
+
+    #[error("Ошибка модели анализа настроений: {0}")]
+    ModelError(String),
+
+    #[error("Исчерпан лимит запросов API: {0}")]
+    RateLimited(String),
+
+    #[error("Некорректный поисковый запрос: {0}")]
+    InvalidQuery(String),
 }
 
 // Определяем псевдоним Result с фиксированным типом ошибки
@@ -49,4 +49,10 @@ impl BitcoinNewsHolder {
         let news = self.news.lock().await;
         Ok(news.len())
     }
-}
\ No newline at end of file
+}
+
+impl Default for BitcoinNewsHolder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
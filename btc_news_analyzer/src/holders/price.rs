@@ -46,4 +46,10 @@ impl BitcoinPriceHolder {
         let prices = self.prices.lock().await;
         Ok(prices.len())
     }
-}
\ No newline at end of file
+}
+
+impl Default for BitcoinPriceHolder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,142 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Поддерживаемые языки ответов. Каталоги сообщений лежат в `locales/*.json`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Locale {
+    #[default]
+    En,
+    Ru,
+    De,
+    Es,
+}
+
+impl Locale {
+    pub const ALL: [Locale; 4] = [Locale::En, Locale::Ru, Locale::De, Locale::Es];
+
+    pub fn code(&self) -> &'static str {
+        match self {
+            Locale::En => "en",
+            Locale::Ru => "ru",
+            Locale::De => "de",
+            Locale::Es => "es",
+        }
+    }
+
+    /// Разбирает языковой тег вида "ru", "ru-RU" или "en_US"
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        let primary = tag.trim().split(['-', '_']).next()?.to_lowercase();
+        Locale::ALL.into_iter().find(|locale| locale.code() == primary)
+    }
+
+    /// Выбирает язык из заголовка Accept-Language с учетом q-весов
+    pub fn from_accept_language(header: &str) -> Option<Locale> {
+        let mut candidates: Vec<(Locale, f32)> = header.split(',')
+            .filter_map(|part| {
+                let mut pieces = part.split(';');
+                let locale = Locale::from_tag(pieces.next()?)?;
+                let quality = pieces
+                    .find_map(|p| p.trim().strip_prefix("q="))
+                    .and_then(|q| q.parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                (quality > 0.0).then_some((locale, quality))
+            })
+            .collect();
+        // Стабильная сортировка сохраняет порядок заголовка при равных весах
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+        candidates.first().map(|(locale, _)| *locale)
+    }
+
+    /// Сообщение из каталога; при отсутствии ключа используется английский каталог
+    pub fn message(&self, key: &str) -> &'static str {
+        let catalogs = catalogs();
+        catalogs.get(self)
+            .and_then(|catalog| catalog.get(key))
+            .or_else(|| catalogs.get(&Locale::En).and_then(|catalog| catalog.get(key)))
+            .map(|s| s.as_str())
+            .unwrap_or("")
+    }
+
+    /// Подставляет аргументы в шаблон сообщения вида "{name}"
+    pub fn format(&self, key: &str, args: &[(&str, String)]) -> String {
+        args.iter().fold(self.message(key).to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), value)
+        })
+    }
+}
+
+type Catalog = HashMap<String, String>;
+
+fn catalogs() -> &'static HashMap<Locale, Catalog> {
+    static CATALOGS: OnceLock<HashMap<Locale, Catalog>> = OnceLock::new();
+    CATALOGS.get_or_init(|| {
+        [
+            (Locale::En, include_str!("../locales/en.json")),
+            (Locale::Ru, include_str!("../locales/ru.json")),
+            (Locale::De, include_str!("../locales/de.json")),
+            (Locale::Es, include_str!("../locales/es.json")),
+        ]
+        .into_iter()
+        .map(|(locale, source)| {
+            let catalog = serde_json::from_str(source)
+                .unwrap_or_else(|e| panic!("Некорректный каталог сообщений {}: {}", locale.code(), e));
+            (locale, catalog)
+        })
+        .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn language_tags_use_primary_subtag() {
+        assert_eq!(Locale::from_tag("ru-RU"), Some(Locale::Ru));
+        assert_eq!(Locale::from_tag(" en_US "), Some(Locale::En));
+        assert_eq!(Locale::from_tag("DE"), Some(Locale::De));
+        assert_eq!(Locale::from_tag("pt"), None);
+        assert_eq!(Locale::from_tag(""), None);
+    }
+
+    #[test]
+    fn accept_language_respects_quality_weights() {
+        assert_eq!(Locale::from_accept_language("fr-FR, ru;q=0.8, en;q=0.9"), Some(Locale::En));
+        assert_eq!(Locale::from_accept_language("de-DE,de;q=0.9"), Some(Locale::De));
+        // При равных весах побеждает язык, указанный раньше
+        assert_eq!(Locale::from_accept_language("es, ru"), Some(Locale::Es));
+        // q=0 означает отказ от языка
+        assert_eq!(Locale::from_accept_language("ru;q=0, en;q=0.1"), Some(Locale::En));
+    }
+
+    #[test]
+    fn unsupported_or_malformed_accept_language() {
+        assert_eq!(Locale::from_accept_language("fr, ja"), None);
+        assert_eq!(Locale::from_accept_language(""), None);
+        assert_eq!(Locale::from_accept_language("*"), None);
+        assert_eq!(Locale::from_accept_language(" , ;q=0.5"), None);
+        // Нечитаемый вес считается равным 1
+        assert_eq!(Locale::from_accept_language("en;q=0.5, ru;q=abc"), Some(Locale::Ru));
+    }
+
+    #[test]
+    fn catalogs_cover_every_english_key() {
+        let catalogs = catalogs();
+        let english = &catalogs[&Locale::En];
+        assert!(!english.is_empty());
+        for locale in Locale::ALL {
+            for key in english.keys() {
+                assert!(catalogs[&locale].contains_key(key), "{} lacks {}", locale.code(), key);
+                assert!(!locale.message(key).is_empty(), "{} has empty {}", locale.code(), key);
+            }
+        }
+    }
+
+    #[test]
+    fn format_substitutes_arguments_and_tolerates_unknown_keys() {
+        let summary = Locale::Ru.format("summary", &[("direction", "выросла".to_string())]);
+        assert!(summary.starts_with("За анализируемый период цена Bitcoin выросла"));
+        assert_eq!(Locale::De.message("no.such.key"), "");
+        assert_eq!(Locale::Es.format("no.such.key", &[("direction", "x".to_string())]), "");
+    }
+}
@@ -1,18 +1,22 @@
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
+pub mod analytics;
 pub mod config;
 pub mod errors;
 pub mod holders;
+pub mod i18n;
 pub mod models;
 pub mod routers;
+pub mod sentiment;
 pub mod services;
 
-pub use config::AppConfig;
+pub use config::{AppConfig, ArticleFetchConfig, AspectConfig, ChunkingConfig, DecisionConfig, DedupConfig, DecisionOverrides, EndpointsConfig, EntityConfig, EntityDefinition, EntityPattern, FileSourcesConfig, GdeltConfig, HuggingFaceConfig, JsonSourceConfig, LanguageConfig, NewsApiConfig, NewsSourcesConfig, OnnxConfig, QueryConfig, RankingConfig, RedditConfig, RelevanceConfig, SentimentConfig, SentimentEngine, SeriesGranularity};
 pub use errors::{BitcoinAnalysisError, Result};
 pub use holders::{BitcoinNewsHolder, BitcoinPriceHolder};
-pub use models::{AmountDays, BitcoinNews, BitcoinPrice, AnalysisResult, PriceStatistics, NewsStatistics, NewsItem};
-pub use services::{DataCollectorService, DataMakerDecisionService, DataProcessorService};
+pub use i18n::Locale;
+pub use models::{AmountDays, BitcoinNews, BitcoinPrice, AnalysisResult, PriceStatistics, NewsStatistics, NewsItem, CorrelationAnalysis, LagCorrelation, SentimentSeriesPoint, SentimentTimeSeries, BacktestParams, BacktestReport, BacktestStep, DecisionExplanation, SentimentBreakdown, ConfidenceBreakdown, ConfidenceCriterion, LanguageStatistics, SourceKind, SourceKindStatistics, AspectSentiment, AspectSummary, EntityMention, EntitySummary, NewsApiUsage};
+pub use services::{ArticleFetcher, BacktestService, CsvPriceSource, DataCollectorService, DataMakerDecisionService, DataProcessorService, FileNewsSource, GdeltSource, JsonNewsSource};
 pub use config::load_config;
 
 #[derive(Clone)]
@@ -20,5 +24,6 @@ pub struct AppState {
     pub collector: DataCollectorService,
     pub processor: DataProcessorService,
     pub decision: DataMakerDecisionService,
+    pub backtest: BacktestService,
     pub amount_days: Arc<Mutex<AmountDays>>,
 }
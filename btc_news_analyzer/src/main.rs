@@ -4,7 +4,7 @@ use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt, EnvFilte
 
 use btc_news_analyzer::{
     AppState, AmountDays, BitcoinNewsHolder, BitcoinPriceHolder,
-    DataCollectorService, DataMakerDecisionService, DataProcessorService,
+    BacktestService, DataCollectorService, DataMakerDecisionService, DataProcessorService,
     load_config
 };
 use btc_news_analyzer::routers::create_routes;
@@ -41,7 +41,13 @@ async fn main() -> anyhow::Result<()> {
             news_holder.clone(),
             config.clone(),
         ),
-        decision: DataMakerDecisionService::new(price_holder, news_holder, amount_days.clone()),
+        decision: DataMakerDecisionService::new(
+            price_holder.clone(),
+            news_holder.clone(),
+            amount_days.clone(),
+            config.clone(),
+        ),
+        backtest: BacktestService::new(price_holder, news_holder, config.clone()),
         amount_days,
     };
 
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
-use chrono::NaiveDate;
+use chrono::{DateTime, NaiveDate, Utc};
+
+use crate::config::DecisionConfig;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BitcoinPrice {
@@ -7,13 +9,104 @@ pub struct BitcoinPrice {
     pub price: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct BitcoinNews {
     pub title: String,
     pub content: String,
     pub is_positive: Option<bool>,
     pub url: Option<String>,
     pub published_at: Option<String>,
+    #[serde(default)]
+    pub sentiment_score: Option<f64>, // непрерывная оценка -1.0..1.0
+    #[serde(default)]
+    pub sentiment_confidence: Option<f64>, // уверенность модели в метке, 0.0..1.0
+    #[serde(default)]
+    pub duplicate_count: usize, // сколько копий этой новости удалено при дедупликации
+    #[serde(default)]
+    pub story_id: Option<usize>, // сюжет, к которому отнесена новость
+    #[serde(default)]
+    pub language: Option<String>, // ISO 639-1, определяется по заголовку и тексту
+    #[serde(default)]
+    pub aspects: Vec<AspectSentiment>, // темы новости и настроение по каждой
+    #[serde(default)]
+    pub entities: Vec<EntityMention>, // биржи, регуляторы, компании, персоны, ETF
+    #[serde(default)]
+    pub full_text: Option<String>, // основной текст страницы статьи, если он загружен
+    #[serde(default)]
+    pub relevance: Option<f64>, // релевантность теме запроса, 0.0..1.0
+    #[serde(default)]
+    pub source_kind: SourceKind,
+    #[serde(default)]
+    pub source: Option<String>, // издание или домен по данным источника
+    #[serde(default)]
+    pub source_tone: Option<f64>, // тон по данным источника (GDELT: примерно -10..10)
+}
+
+/// Тип источника: редакционные СМИ или обсуждения в соцсетях
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SourceKind {
+    #[default]
+    Editorial,
+    Social,
+}
+
+impl SourceKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SourceKind::Editorial => "editorial",
+            SourceKind::Social => "social",
+        }
+    }
+}
+
+/// Сущность, упомянутая в новости
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EntityMention {
+    pub name: String, // каноническое имя
+    pub kind: String, // "exchange", "regulator", "company", "person", "etf", "asset", ...
+    pub mentions: usize,
+}
+
+/// Настроение новости по одному аспекту (регулирование, ETF, ...)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AspectSentiment {
+    pub aspect: String,
+    pub score: f64, // -1.0..1.0
+    pub mentions: usize, // 0, если аспект определён zero-shot моделью
+}
+
+impl BitcoinNews {
+    /// Время публикации в UTC, если `published_at` задан в формате RFC 3339
+    pub fn published_datetime(&self) -> Option<DateTime<Utc>> {
+        self.published_at.as_deref()
+            .and_then(|s| DateTime::parse_from_rfc3339(s).ok())
+            .map(|dt| dt.with_timezone(&Utc))
+    }
+
+    pub fn published_date(&self) -> Option<NaiveDate> {
+        self.published_datetime().map(|dt| dt.date_naive())
+    }
+
+    /// Домен источника без префикса www.
+    pub fn source_domain(&self) -> Option<String> {
+        let url = reqwest::Url::parse(self.url.as_deref()?).ok()?;
+        let host = url.host_str()?;
+        Some(host.trim_start_matches("www.").to_lowercase())
+    }
+
+    /// Числовая оценка настроения: непрерывная, если движок ее вернул,
+    /// иначе +1 позитивная, -1 негативная, 0 нейтральная
+    pub fn sentiment_value(&self) -> f64 {
+        if let Some(score) = self.sentiment_score {
+            return score;
+        }
+        match self.is_positive {
+            Some(true) => 1.0,
+            Some(false) => -1.0,
+            None => 0.0,
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -30,10 +123,56 @@ pub struct PriceStatistics {
     pub highest_price: f64,
     pub lowest_price: f64,
     pub average_price: f64,
-    pub volatility: f64,
+    pub daily_volatility: f64, // стандартное отклонение дневных лог-доходностей, %
+    pub annualized_volatility: f64, // daily_volatility * sqrt(365), %
+    pub max_drawdown: f64, // максимальная просадка от пика, %
+    pub price_range_percentage: f64, // (highest - lowest) / lowest, %
+    pub average_abs_daily_change: f64, // среднее абсолютное изменение цены закрытия за день, %
+    pub data_points: usize,
     pub trend: String, // "bullish", "bearish", "sideways"
 }
 
+/// Агрегированное настроение по аспекту за период
+#[derive(Debug, Serialize)]
+pub struct AspectSummary {
+    pub aspect: String,
+    pub article_count: usize,
+    pub positive_count: usize,
+    pub negative_count: usize,
+    pub average_score: f64,
+    pub share_of_articles: f64, // доля статей с этим аспектом, %
+}
+
+/// Самые упоминаемые сущности за период
+#[derive(Debug, Serialize)]
+pub struct EntitySummary {
+    pub name: String,
+    pub kind: String,
+    pub article_count: usize,
+    pub mention_count: usize,
+    pub average_sentiment: f64, // среднее настроение статей с упоминанием, -1.0..1.0
+}
+
+#[derive(Debug, Serialize)]
+pub struct LanguageStatistics {
+    pub language: String, // ISO 639-1 или "unknown"
+    pub total: usize,
+    pub positive_count: usize,
+    pub negative_count: usize,
+    pub neutral_count: usize,
+    pub sentiment_score: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SourceKindStatistics {
+    pub source_kind: String, // "editorial" или "social"
+    pub total: usize,
+    pub positive_count: usize,
+    pub negative_count: usize,
+    pub neutral_count: usize,
+    pub sentiment_score: f64,
+}
+
 #[derive(Debug, Serialize)]
 pub struct NewsStatistics {
     pub total_analyzed: usize,
@@ -43,6 +182,11 @@ pub struct NewsStatistics {
     pub positive_percentage: f64,
     pub negative_percentage: f64,
     pub sentiment_score: f64, // -1.0 to 1.0
+    pub statistics_unit: String, // "article" или "story"
+    pub story_count: usize,
+    pub duplicates_removed: usize,
+    pub by_language: Vec<LanguageStatistics>,
+    pub by_source_kind: Vec<SourceKindStatistics>, // редакционные новости и соцсети отдельно
 }
 
 #[derive(Debug, Serialize)]
@@ -52,6 +196,125 @@ pub struct NewsItem {
     pub confidence: f64,
     pub published_at: Option<String>,
     pub url: Option<String>,
+    pub ranking_score: f64, // 0.0..1.0
+    pub ranking_reasons: Vec<String>,
+    pub relevance: Option<f64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LagCorrelation {
+    pub lag_days: i64, // sentiment дня d против доходности дня d + lag_days
+    pub pearson: Option<f64>,
+    pub spearman: Option<f64>,
+    pub sample_size: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CorrelationAnalysis {
+    pub days_with_news: usize,
+    pub days_with_returns: usize,
+    pub lags: Vec<LagCorrelation>,
+    pub strongest_lag_days: Option<i64>, // лаг с максимальным |pearson|
+}
+
+#[derive(Debug, Serialize)]
+pub struct SentimentSeriesPoint {
+    pub period_start: String, // RFC 3339, начало дня или часа
+    pub article_count: usize,
+    pub positive_count: usize,
+    pub negative_count: usize,
+    pub neutral_count: usize,
+    pub mean_sentiment_score: Option<f64>, // None, если в периоде нет новостей
+    pub close_price: Option<f64>, // цена закрытия дня, к которому относится период
+}
+
+#[derive(Debug, Serialize)]
+pub struct SentimentTimeSeries {
+    pub granularity: String, // "daily", "hourly"
+    pub points: Vec<SentimentSeriesPoint>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BacktestParams {
+    pub window_days: u32, // окно, по которому строится сигнал на каждом шаге
+    pub fee_bps: f64, // комиссия в базисных пунктах за единицу изменения позиции
+    pub allow_short: bool, // false — стратегия long/flat
+}
+
+impl Default for BacktestParams {
+    fn default() -> Self {
+        BacktestParams {
+            window_days: 7,
+            fee_bps: 10.0,
+            allow_short: true,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct BacktestStep {
+    pub date: NaiveDate,
+    pub signal: String,
+    pub position: f64, // 1.0 long, 0.0 flat, -1.0 short
+    pub market_return: f64, // доходность следующего дня, %
+    pub strategy_return: f64, // с учетом комиссии, %
+    pub equity: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BacktestReport {
+    pub timestamp: String,
+    pub start_date: NaiveDate,
+    pub end_date: NaiveDate,
+    pub params: BacktestParams,
+    pub steps_count: usize,
+    pub trades: usize,
+    pub hit_rate: Option<f64>, // доля верных направлений среди дней в позиции
+    pub total_return: f64, // %
+    pub annualized_return: f64, // %
+    pub buy_and_hold_return: f64, // %
+    pub sharpe_ratio: Option<f64>, // годовой, без безрисковой ставки
+    pub max_drawdown: f64, // %
+    pub liquidated: bool, // капитал обнулился и симуляция остановлена досрочно
+    pub steps: Vec<BacktestStep>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SentimentBreakdown {
+    pub label: String,
+    pub price_change_percentage: f64,
+    pub price_score: f64, // -1.0, -0.5, 0.0, 0.5, 1.0
+    pub price_band: String, // порог изменения цены, который сработал
+    pub news_sentiment_score: f64,
+    pub price_weight: f64,
+    pub news_weight: f64,
+    pub weighted_price_score: f64,
+    pub weighted_news_score: f64,
+    pub combined_score: f64,
+    pub sentiment_band: String, // порог combined_score, который определил метку
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConfidenceCriterion {
+    pub name: String,
+    pub passed: bool,
+    pub observed: f64,
+    pub threshold: f64,
+    pub rule: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ConfidenceBreakdown {
+    pub level: String,
+    pub rule_applied: String,
+    pub criteria: Vec<ConfidenceCriterion>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DecisionExplanation {
+    pub market_sentiment: SentimentBreakdown,
+    pub confidence: ConfidenceBreakdown,
 }
 
 #[derive(Debug, Serialize)]
@@ -62,7 +325,29 @@ pub struct AnalysisResult {
     pub price_statistics: PriceStatistics,
     pub news_statistics: NewsStatistics,
     pub key_news: Vec<NewsItem>,
+    pub aspect_sentiment: Vec<AspectSummary>,
+    pub top_entities: Vec<EntitySummary>,
+    pub sentiment_series: SentimentTimeSeries,
+    pub sentiment_price_correlation: CorrelationAnalysis,
     pub market_sentiment: String, // "very_bullish", "bullish", "neutral", "bearish", "very_bearish"
+    pub market_sentiment_description: String, // на языке ответа
     pub confidence_level: String, // "high", "medium", "low"
     pub summary: String,
+    pub language: String, // код языка summary: "en", "ru", "de", "es"
+    pub decision_parameters: DecisionConfig, // фактически примененные веса и пороги
+    pub decision_explanation: DecisionExplanation,
+    pub newsapi_usage: Option<NewsApiUsage>, // заполняется, если новости собирались через NewsAPI
+}
+
+/// Использование NewsAPI при последнем сборе новостей
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct NewsApiUsage {
+    pub requests_made: usize,
+    pub requests_today: usize,              // с начала суток UTC
+    pub requests_remaining: Option<usize>,  // до суточного лимита тарифа
+    pub total_results: usize,               // totalResults по всем языкам
+    pub articles_received: usize,
+    pub history_days: u32,                  // фактическая глубина запроса после ограничения тарифа
+    pub history_limited: bool,
+    pub stop_reason: Option<String>,        // "rateLimited", "maximumResultsReached", "daily_request_limit"
 }
\ No newline at end of file
@@ -1,6 +1,6 @@
 use axum::{
-    extract::State,
-    http::StatusCode,
+    extract::{Query, State},
+    http::{header::ACCEPT_LANGUAGE, HeaderMap, StatusCode},
     response::Json,
     routing::{get, post},
     Router,
@@ -8,11 +8,49 @@ use axum::{
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 
+use crate::config::DecisionOverrides;
+use crate::i18n::Locale;
+use crate::models::{BacktestParams, BitcoinNews, BitcoinPrice};
 use crate::AppState;
 
 #[derive(Deserialize)]
 pub struct AnalysisRequest {
     pub amount_days: u32,
+    // Переопределения весов и порогов секции [decision] для этого запроса
+    #[serde(default)]
+    pub decision: Option<DecisionOverrides>,
+    // Язык резюме; имеет приоритет над заголовком Accept-Language
+    #[serde(default)]
+    pub lang: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct LanguageQuery {
+    pub lang: Option<String>,
+}
+
+/// Язык ответа: параметр `lang`, затем Accept-Language, затем английский по умолчанию
+fn resolve_locale(lang: Option<&str>, headers: &HeaderMap) -> Locale {
+    if let Some(tag) = lang {
+        match Locale::from_tag(tag) {
+            Some(locale) => return locale,
+            None => tracing::warn!("Неподдерживаемый язык '{}', используется Accept-Language", tag),
+        }
+    }
+
+    headers.get(ACCEPT_LANGUAGE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(Locale::from_accept_language)
+        .unwrap_or_default()
+}
+
+#[derive(Deserialize)]
+pub struct BacktestRequest {
+    #[serde(flatten)]
+    pub params: BacktestParams,
+    // Если история не передана, используются данные последнего анализа
+    pub prices: Option<Vec<BitcoinPrice>>,
+    pub news: Option<Vec<BitcoinNews>>,
 }
 
 #[derive(Serialize)]
@@ -32,6 +70,7 @@ pub struct StatusResponse {
 // Основной обработчик анализа Bitcoin
 pub async fn bitcoin_analysis(
     State(state): State<AppState>,
+    headers: HeaderMap,
     Json(req): Json<AnalysisRequest>,
 ) -> Result<Json<Value>, StatusCode> {
     // Валидация входных данных
@@ -40,6 +79,17 @@ pub async fn bitcoin_analysis(
         return Err(StatusCode::BAD_REQUEST);
     }
 
+    let decision_params = match &req.decision {
+        Some(overrides) => state.decision.parameters().with_overrides(overrides),
+        None => state.decision.parameters().clone(),
+    };
+    if let Err(e) = decision_params.validate() {
+        tracing::warn!("Некорректные параметры решения: {}", e);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    let locale = resolve_locale(req.lang.as_deref(), &headers);
+
     tracing::info!("Начинаем анализ Bitcoin за {} дней", req.amount_days);
 
     // Обновляем количество дней
@@ -69,8 +119,9 @@ pub async fn bitcoin_analysis(
     }
 
     // Принимаем решение
-    match state.decision.make_decision().await {
-        Ok(analysis_result) => {
+    match state.decision.make_decision_with(&decision_params, locale).await {
+        Ok(mut analysis_result) => {
+            analysis_result.newsapi_usage = state.collector.newsapi_usage().await;
             tracing::info!("Анализ успешно завершен");
             Ok(Json(serde_json::to_value(analysis_result).unwrap()))
         },
@@ -85,6 +136,47 @@ pub async fn bitcoin_analysis(
     }
 }
 
+// Бэктест сигнала market_sentiment на исторических данных
+pub async fn backtest(
+    State(state): State<AppState>,
+    Json(req): Json<BacktestRequest>,
+) -> Result<Json<Value>, StatusCode> {
+    if req.params.window_days == 0 || req.params.window_days > 365 {
+        tracing::warn!("Некорректное окно бэктеста: {}", req.params.window_days);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+    if !(0.0..=1000.0).contains(&req.params.fee_bps) {
+        tracing::warn!("Некорректная комиссия бэктеста: {}", req.params.fee_bps);
+        return Err(StatusCode::BAD_REQUEST);
+    }
+
+    tracing::info!("Запуск бэктеста с окном {} дней", req.params.window_days);
+
+    let result = match req.prices {
+        Some(prices) => {
+            state.backtest
+                .run(prices, req.news.unwrap_or_default(), &req.params)
+                .await
+        }
+        None => state.backtest.run_on_stored(&req.params).await,
+    };
+
+    match result {
+        Ok(report) => {
+            tracing::info!("Бэктест завершен: {} шагов", report.steps_count);
+            Ok(Json(serde_json::to_value(report).unwrap()))
+        },
+        Err(e) => {
+            tracing::error!("Ошибка бэктеста: {}", e);
+            Ok(Json(json!({
+                "status": "error",
+                "message": format!("Ошибка бэктеста: {}", e),
+                "error_type": "backtest_error"
+            })))
+        }
+    }
+}
+
 // Проверка здоровья сервиса
 pub async fn health_check() -> Json<HealthResponse> {
     Json(HealthResponse {
@@ -108,35 +200,38 @@ pub async fn get_status(State(state): State<AppState>) -> Json<StatusResponse> {
             "/".to_string(),
             "/status".to_string(),
             "/api/bitcoin-analysis".to_string(),
+            "/api/backtest".to_string(),
         ],
     })
 }
 
 // Простой анализ без параметров (по умолчанию 7 дней)
-pub async fn simple_analysis(State(state): State<AppState>) -> Result<Json<Value>, StatusCode> {
-    let req = AnalysisRequest { amount_days: 7 };
-    bitcoin_analysis(State(state), Json(req)).await
+pub async fn simple_analysis(
+    State(state): State<AppState>,
+    Query(query): Query<LanguageQuery>,
+    headers: HeaderMap,
+) -> Result<Json<Value>, StatusCode> {
+    let req = AnalysisRequest { amount_days: 7, decision: None, lang: query.lang };
+    bitcoin_analysis(State(state), headers, Json(req)).await
 }
 
 // Тестовый эндпоинт для проверки актуальных дат
-pub async fn test_dates() -> Json<Value> {
+pub async fn test_dates(State(state): State<AppState>) -> Json<Value> {
     let now = chrono::Utc::now().date_naive();
     let week_ago = now.checked_sub_days(chrono::Days::new(7)).unwrap();
     let two_weeks_ago = now.checked_sub_days(chrono::Days::new(14)).unwrap();
     let month_ago = now.checked_sub_days(chrono::Days::new(30)).unwrap();
+    let available_apis: serde_json::Map<String, Value> = state.collector.price_api_urls(7)
+        .into_iter()
+        .map(|(name, url)| (name.to_string(), Value::String(url)))
+        .collect();
 
     Json(json!({
         "current_date": now.format("%Y-%m-%d").to_string(),
         "week_ago": week_ago.format("%Y-%m-%d").to_string(),
         "two_weeks_ago": two_weeks_ago.format("%Y-%m-%d").to_string(),
         "month_ago": month_ago.format("%Y-%m-%d").to_string(),
-        "available_apis": {
-            "coingecko_current": "https://api.coingecko.com/api/v3/coins/bitcoin/market_chart?vs_currency=usd&days=7&interval=daily".to_string(),
-            "binance_klines": "https://api.binance.com/api/v3/klines?symbol=BTCUSDT&interval=1d&limit=7",
-            "coincap_history": format!("https://api.coincap.io/v2/assets/bitcoin/history?interval=d1&start={}&end={}",
-                week_ago.and_hms_opt(0, 0, 0).unwrap().and_utc().timestamp() * 1000,
-                now.and_hms_opt(23, 59, 59).unwrap().and_utc().timestamp() * 1000)
-        },
+        "available_apis": available_apis,
         "note": "Все данные актуальны на текущую дату"
     }))
 }
@@ -149,5 +244,6 @@ pub fn create_routes(state: AppState) -> Router {
         .route("/test-dates", get(test_dates))
         .route("/api/bitcoin-analysis", post(bitcoin_analysis))
         .route("/analyze", get(simple_analysis))
+        .route("/api/backtest", post(backtest))
         .with_state(state)
 }
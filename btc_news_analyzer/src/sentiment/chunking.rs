@@ -0,0 +1,164 @@
+// Разбиение текста на окна по лимиту токенов для удалённых моделей, токенизатор которых недоступен
+use crate::config::ChunkingConfig;
+
+// <s> и </s> (или [CLS]/[SEP]), которые модель добавляет к каждому входу
+pub const SPECIAL_TOKENS: usize = 2;
+// Доля бюджета окна, оставляемая про запас на случай заниженной оценки, %
+const SAFETY_MARGIN_PERCENT: usize = 10;
+
+/// Фрагмент текста и оценка его длины в токенах
+#[derive(Debug, Clone, PartialEq)]
+pub struct TextWindow {
+    pub text: String,
+    pub tokens: usize,
+}
+
+/// Эвристическая оценка числа BPE-токенов без токенизатора модели: латиница и цифры —
+/// по 2 символа на токен, знак ASCII — отдельный токен, прочие символы — токен на каждый
+/// байт UTF-8. Для обычного текста оценка завышена, но верхней границей не является:
+/// редкие слова и числа BPE может дробить мельче, поэтому окна оставляют запас
+/// `SAFETY_MARGIN_PERCENT`, а запросы к HuggingFace просят обрезку на сервере
+pub fn estimate_tokens(text: &str) -> usize {
+    text.split_whitespace().map(word_tokens).sum()
+}
+
+fn word_tokens(word: &str) -> usize {
+    let mut tokens = 0;
+    let mut ascii_run = 0usize;
+    for c in word.chars() {
+        if c.is_ascii_alphanumeric() {
+            ascii_run += 1;
+            continue;
+        }
+        tokens += ascii_run.div_ceil(2) + c.len_utf8();
+        ascii_run = 0;
+    }
+    tokens + ascii_run.div_ceil(2)
+}
+
+/// Окна, оценка длины которых с запасом укладывается в `max_tokens` (с учётом служебных
+/// токенов), с перекрытием `overlap_tokens`. Короткий текст даёт одно окно, окна сверх
+/// `max_windows` отбрасываются
+pub fn split_into_windows(text: &str, config: &ChunkingConfig) -> Vec<TextWindow> {
+    let available = config.max_tokens.saturating_sub(SPECIAL_TOKENS);
+    let budget = (available - available * SAFETY_MARGIN_PERCENT / 100).max(1);
+    let words: Vec<(&str, usize)> = text
+        .split_whitespace()
+        .map(|word| {
+            // Слово длиннее окна обрезаем по границе символа: байт — не больше одного токена
+            let end = word.char_indices()
+                .map(|(start, c)| start + c.len_utf8())
+                .take_while(|&end| end <= budget)
+                .last()
+                .unwrap_or(0);
+            let word = &word[..end];
+            (word, word_tokens(word))
+        })
+        .collect();
+
+    let mut windows = Vec::new();
+    let mut start = 0;
+    while start < words.len() {
+        if windows.len() == config.max_windows {
+            tracing::debug!("Текст длиннее {} окон, остаток не анализируется", config.max_windows);
+            break;
+        }
+
+        let mut end = start;
+        let mut tokens = 0;
+        while end < words.len() && (end == start || tokens + words[end].1 <= budget) {
+            tokens += words[end].1;
+            end += 1;
+        }
+        windows.push(TextWindow {
+            text: words[start..end].iter().map(|(word, _)| *word).collect::<Vec<_>>().join(" "),
+            tokens,
+        });
+        if end == words.len() {
+            break;
+        }
+
+        // Следующее окно начинается с хвоста текущего длиной до overlap_tokens
+        let mut next = end;
+        let mut overlap = 0;
+        while next > start + 1 && overlap + words[next - 1].1 <= config.overlap_tokens {
+            overlap += words[next - 1].1;
+            next -= 1;
+        }
+        start = next;
+    }
+    windows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // 34 - 2 служебных = 32 токена, из них 10% (3 токена) в запасе
+    const BUDGET: usize = 29;
+
+    fn chunking() -> ChunkingConfig {
+        ChunkingConfig { max_tokens: 34, overlap_tokens: 8, max_windows: 8 }
+    }
+
+    fn numbered_words() -> String {
+        (0..100).map(|i| format!("word{:03}", i)).collect::<Vec<_>>().join(" ")
+    }
+
+    #[test]
+    fn token_estimate_by_script() {
+        assert_eq!(estimate_tokens(""), 0);
+        assert_eq!(estimate_tokens("Bitcoin"), 4);
+        assert_eq!(estimate_tokens("BTC, ETH!"), 2 + 1 + 2 + 1);
+        // Кириллица — до токена на байт UTF-8: 7 букв по 2 байта
+        assert_eq!(estimate_tokens("Биткоин"), 14);
+        assert_eq!(estimate_tokens("ETF «Биткоин»"), 2 + 2 + 14 + 2);
+    }
+
+    #[test]
+    fn empty_text_has_no_windows() {
+        assert!(split_into_windows("", &chunking()).is_empty());
+        assert!(split_into_windows(" \n\t ", &chunking()).is_empty());
+    }
+
+    #[test]
+    fn short_text_is_a_single_window() {
+        let short = split_into_windows("Bitcoin ETF inflows surge", &chunking());
+        assert_eq!(short.len(), 1);
+        assert_eq!(short[0].text, "Bitcoin ETF inflows surge");
+    }
+
+    #[test]
+    fn windows_keep_safety_margin_and_overlap() {
+        let windows = split_into_windows(&numbered_words(), &ChunkingConfig { max_windows: 32, ..chunking() });
+        assert!(windows.len() > 1);
+        assert!(windows.iter().all(|w| w.tokens <= BUDGET && w.tokens == estimate_tokens(&w.text)));
+        assert!(windows[0].text.starts_with("word000"));
+        assert!(windows.last().unwrap().text.ends_with("word099"));
+        for pair in windows.windows(2) {
+            let last_word = pair[0].text.rsplit(' ').next().unwrap();
+            assert!(pair[1].text.contains(last_word));
+        }
+    }
+
+    #[test]
+    fn non_latin_windows_fit_byte_budget() {
+        let russian = "Биткоин обновил исторический максимум на фоне притока средств в биржевые фонды. ".repeat(20);
+        let windows = split_into_windows(&russian, &chunking());
+        assert!(windows.len() > 1);
+        assert!(windows.iter().all(|w| w.tokens <= BUDGET && w.text.len() <= BUDGET + w.text.matches(' ').count()));
+    }
+
+    #[test]
+    fn long_word_is_cut_at_char_boundary() {
+        let windows = split_into_windows(&"ж".repeat(100), &chunking());
+        assert_eq!(windows.len(), 1);
+        assert_eq!(windows[0].text.chars().count(), BUDGET / 2);
+    }
+
+    #[test]
+    fn windows_beyond_limit_are_dropped() {
+        let capped = split_into_windows(&numbered_words(), &ChunkingConfig { max_windows: 2, ..chunking() });
+        assert_eq!(capped.len(), 2);
+    }
+}
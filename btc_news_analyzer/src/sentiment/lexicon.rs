@@ -0,0 +1,417 @@
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::errors::{BitcoinAnalysisError, Result};
+
+// Константы VADER (Hutto & Gilbert, 2014)
+const NEGATION_SCALAR: f64 = -0.74;
+const CAPS_INCREMENT: f64 = 0.733;
+const EXCLAMATION_INCREMENT: f64 = 0.292;
+const QUESTION_INCREMENT: f64 = 0.18;
+const NORMALIZATION_ALPHA: f64 = 15.0;
+// Сколько слов назад действуют отрицания и усилители
+const SCOPE_WINDOW: usize = 3;
+// Порог compound, начиная с которого текст считается позитивным или негативным
+const LABEL_THRESHOLD: f64 = 0.05;
+
+/// Результат словарного анализа
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LexiconScore {
+    pub compound: f64, // -1.0 .. 1.0
+    pub positive: f64, // доли позитивной, негативной и нейтральной лексики
+    pub negative: f64,
+    pub neutral: f64,
+}
+
+impl LexiconScore {
+    pub fn is_positive(&self) -> bool {
+        self.compound >= LABEL_THRESHOLD
+    }
+
+    pub fn is_negative(&self) -> bool {
+        self.compound <= -LABEL_THRESHOLD
+    }
+
+    /// Уверенность в метке: модуль compound, для нейтральных текстов — близость к нулю
+    pub fn confidence(&self) -> f64 {
+        if self.is_positive() || self.is_negative() {
+            self.compound.abs()
+        } else {
+            1.0 - self.compound.abs() / LABEL_THRESHOLD
+        }
+    }
+}
+
+struct Word {
+    text: String,
+    shouting: bool, // слово написано заглавными буквами
+    clause: usize,
+}
+
+/// Словарный анализатор настроений в стиле VADER
+#[derive(Debug, Clone, Default)]
+pub struct Lexicon {
+    words: HashMap<String, f64>,
+    stems: Vec<(String, f64)>,
+    phrases: HashMap<String, f64>,
+    max_phrase_len: usize,
+    negations: HashSet<String>,
+    boosters: HashMap<String, f64>,
+    contrasts: HashSet<String>,
+}
+
+impl Lexicon {
+    pub fn load(path: &Path) -> Result<Lexicon> {
+        let source = std::fs::read_to_string(path).map_err(|e| {
+            BitcoinAnalysisError::InvalidDataFormat(format!("Не удалось прочитать словарь {}: {}", path.display(), e))
+        })?;
+        Lexicon::parse(&source)
+    }
+
+    /// Разбирает словарь: строки `термин<TAB>валентность` и директивы `@negation`, `@booster`, `@contrast`
+    pub fn parse(source: &str) -> Result<Lexicon> {
+        let mut lexicon = Lexicon {
+            max_phrase_len: 1,
+            ..Lexicon::default()
+        };
+
+        for (line_number, line) in source.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split('\t').map(str::trim).collect();
+            let invalid = || BitcoinAnalysisError::InvalidDataFormat(format!(
+                "Некорректная строка словаря {}: {}",
+                line_number + 1,
+                line
+            ));
+            let parse_value = |value: Option<&&str>| value.and_then(|v| v.parse::<f64>().ok()).ok_or_else(invalid);
+
+            match fields[0] {
+                "@negation" => {
+                    lexicon.negations.insert(fields.get(1).ok_or_else(invalid)?.to_lowercase());
+                }
+                "@booster" => {
+                    let word = fields.get(1).ok_or_else(invalid)?.to_lowercase();
+                    lexicon.boosters.insert(word, parse_value(fields.get(2))?);
+                }
+                "@contrast" => {
+                    lexicon.contrasts.insert(fields.get(1).ok_or_else(invalid)?.to_lowercase());
+                }
+                term => {
+                    let valence = parse_value(fields.get(1))?;
+                    let term = term.to_lowercase();
+                    let word_count = term.split_whitespace().count();
+                    if word_count > 1 {
+                        lexicon.max_phrase_len = lexicon.max_phrase_len.max(word_count);
+                        lexicon.phrases.insert(term.split_whitespace().collect::<Vec<_>>().join(" "), valence);
+                    } else if let Some(stem) = term.strip_suffix('*') {
+                        lexicon.stems.push((stem.to_string(), valence));
+                    } else {
+                        lexicon.words.insert(term, valence);
+                    }
+                }
+            }
+        }
+
+        // Более длинные основы проверяются первыми
+        lexicon.stems.sort_by_key(|(stem, _)| std::cmp::Reverse(stem.chars().count()));
+        Ok(lexicon)
+    }
+
+    pub fn analyze(&self, text: &str) -> LexiconScore {
+        let (words, exclamations, questions) = tokenize(text);
+        let text_is_shouting = words.iter().all(|w| w.shouting);
+
+        let mut valences: Vec<f64> = Vec::with_capacity(words.len());
+        let mut neutral_words = 0;
+        let mut contrast_at = None;
+        let mut i = 0;
+
+        while i < words.len() {
+            let word = &words[i].text;
+            if self.contrasts.contains(word) {
+                contrast_at = Some(valences.len());
+            }
+            if self.negations.contains(word) || self.boosters.contains_key(word) || self.contrasts.contains(word) {
+                i += 1;
+                continue;
+            }
+
+            let (valence, consumed) = match self.lookup(&words, i) {
+                Some(found) => found,
+                None => {
+                    neutral_words += 1;
+                    i += 1;
+                    continue;
+                }
+            };
+
+            let mut valence = valence;
+            if words[i].shouting && !text_is_shouting {
+                valence += CAPS_INCREMENT * valence.signum();
+            }
+
+            // Усилители и отрицания в пределах окна и той же части предложения
+            let mut negated = false;
+            for distance in 1..=SCOPE_WINDOW.min(i) {
+                let previous = &words[i - distance];
+                if previous.clause != words[i].clause {
+                    break;
+                }
+                if let Some(scalar) = self.boosters.get(&previous.text) {
+                    let damping = 1.0 - 0.05 * (distance - 1) as f64;
+                    valence += scalar * damping * valence.signum();
+                }
+                if self.negations.contains(&previous.text) {
+                    negated = !negated;
+                }
+            }
+            if negated {
+                valence *= NEGATION_SCALAR;
+            }
+
+            valences.push(valence);
+            i += consumed;
+        }
+
+        // Правило противопоставления: после "но" мнение весит больше
+        if let Some(pivot) = contrast_at {
+            for (index, valence) in valences.iter_mut().enumerate() {
+                *valence *= if index < pivot { 0.5 } else { 1.5 };
+            }
+        }
+
+        let mut sum: f64 = valences.iter().sum();
+        if sum != 0.0 {
+            let mut emphasis = exclamations.min(4) as f64 * EXCLAMATION_INCREMENT;
+            if questions > 1 {
+                emphasis += questions.min(3) as f64 * QUESTION_INCREMENT;
+            }
+            sum += emphasis * sum.signum();
+        }
+        let compound = sum / (sum * sum + NORMALIZATION_ALPHA).sqrt();
+
+        let positive_mass: f64 = valences.iter().filter(|v| **v > 0.0).map(|v| v + 1.0).sum();
+        let negative_mass: f64 = valences.iter().filter(|v| **v < 0.0).map(|v| v.abs() + 1.0).sum();
+        let total = positive_mass + negative_mass + neutral_words as f64;
+
+        if total == 0.0 {
+            return LexiconScore { compound, positive: 0.0, negative: 0.0, neutral: 1.0 };
+        }
+        LexiconScore {
+            compound,
+            positive: positive_mass / total,
+            negative: negative_mass / total,
+            neutral: neutral_words as f64 / total,
+        }
+    }
+
+    /// Валентность фразы или слова, начинающихся с позиции `i`, и число занятых слов
+    fn lookup(&self, words: &[Word], i: usize) -> Option<(f64, usize)> {
+        for length in (2..=self.max_phrase_len).rev() {
+            if i + length > words.len() || words[i + length - 1].clause != words[i].clause {
+                continue;
+            }
+            let phrase = words[i..i + length].iter().map(|w| w.text.as_str()).collect::<Vec<_>>().join(" ");
+            if let Some(valence) = self.phrases.get(&phrase) {
+                return Some((*valence, length));
+            }
+        }
+
+        let word = &words[i].text;
+        self.words.get(word).copied()
+            .or_else(|| {
+                self.stems.iter()
+                    .find(|(stem, _)| word.starts_with(stem.as_str()))
+                    .map(|(_, valence)| *valence)
+            })
+            .map(|valence| (valence, 1))
+    }
+}
+
+/// Разбивает текст на слова и эмодзи; пунктуация отделяет части предложения и не
+/// прилипает к словам ("rally," -> "rally")
+fn tokenize(text: &str) -> (Vec<Word>, usize, usize) {
+    let mut words = Vec::new();
+    let mut current = String::new();
+    let mut clause = 0;
+    let mut exclamations = 0;
+    let mut questions = 0;
+
+    let flush = |current: &mut String, words: &mut Vec<Word>, clause: usize| {
+        let token = current.trim_matches(|c| c == '\'' || c == '-' || c == '’');
+        if !token.is_empty() {
+            let letters: Vec<char> = token.chars().filter(|c| c.is_alphabetic()).collect();
+            words.push(Word {
+                text: token.to_lowercase().replace('’', "'"),
+                shouting: letters.len() > 1 && letters.iter().all(|c| c.is_uppercase()),
+                clause,
+            });
+        }
+        current.clear();
+    };
+
+    for c in text.chars() {
+        if c.is_alphanumeric() || c == '\'' || c == '’' || c == '-' {
+            current.push(c);
+            continue;
+        }
+
+        flush(&mut current, &mut words, clause);
+        match c {
+            '!' => {
+                exclamations += 1;
+                clause += 1;
+            }
+            '?' => {
+                questions += 1;
+                clause += 1;
+            }
+            '.' | ',' | ';' | ':' | '(' | ')' | '"' | '«' | '»' | '—' | '–' => clause += 1,
+            c if c.is_whitespace() || c.is_ascii() || c == '\u{fe0f}' || c == '\u{200d}' => {}
+            emoji => words.push(Word {
+                text: emoji.to_string(),
+                shouting: false,
+                clause,
+            }),
+        }
+    }
+    flush(&mut current, &mut words, clause);
+
+    (words, exclamations, questions)
+}
+
+/// Словари по языкам; для языков без собственного словаря используется английский
+#[derive(Debug, Clone)]
+pub struct LexiconSet {
+    lexicons: HashMap<String, Lexicon>,
+}
+
+const DEFAULT_LANGUAGE: &str = "en";
+const BUILTIN_LEXICONS: [(&str, &str); 2] = [
+    ("en", include_str!("../../lexicons/en.tsv")),
+    ("ru", include_str!("../../lexicons/ru.tsv")),
+];
+
+impl LexiconSet {
+    /// Встроенные словари, переопределяемые файлами `<язык>.tsv` из каталога `directory`
+    pub fn load(directory: Option<&Path>) -> LexiconSet {
+        let mut lexicons: HashMap<String, Lexicon> = BUILTIN_LEXICONS.iter()
+            .map(|(language, source)| {
+                let lexicon = Lexicon::parse(source)
+                    .unwrap_or_else(|e| panic!("Встроенный словарь {} некорректен: {}", language, e));
+                (language.to_string(), lexicon)
+            })
+            .collect();
+
+        let Some(directory) = directory else {
+            return LexiconSet { lexicons };
+        };
+        let entries = match std::fs::read_dir(directory) {
+            Ok(entries) => entries,
+            Err(e) => {
+                tracing::warn!("Каталог словарей {} недоступен: {}, используются встроенные", directory.display(), e);
+                return LexiconSet { lexicons };
+            }
+        };
+
+        for path in entries.filter_map(|entry| entry.ok().map(|e| e.path())) {
+            if path.extension().and_then(|e| e.to_str()) != Some("tsv") {
+                continue;
+            }
+            let Some(language) = path.file_stem().and_then(|s| s.to_str()).map(str::to_lowercase) else {
+                continue;
+            };
+            match Lexicon::load(&path) {
+                Ok(lexicon) => {
+                    tracing::info!("Загружен словарь настроений {} из {}", language, path.display());
+                    lexicons.insert(language, lexicon);
+                }
+                Err(e) => tracing::warn!("Словарь {} пропущен: {}", path.display(), e),
+            }
+        }
+
+        LexiconSet { lexicons }
+    }
+
+    pub fn analyze(&self, text: &str, language: Option<&str>) -> LexiconScore {
+        language
+            .and_then(|l| self.lexicons.get(l))
+            .or_else(|| self.lexicons.get(DEFAULT_LANGUAGE))
+            .map(|lexicon| lexicon.analyze(text))
+            .unwrap_or(LexiconScore { compound: 0.0, positive: 0.0, negative: 0.0, neutral: 1.0 })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn score(text: &str) -> f64 {
+        LexiconSet::load(None).analyze(text, Some("en")).compound
+    }
+
+    #[test]
+    fn punctuation_splits_words_and_clauses() {
+        let (words, exclamations, questions) = tokenize("Rally, BTC's SURGE!! Why? 🚀");
+        let texts: Vec<&str> = words.iter().map(|w| w.text.as_str()).collect();
+        assert_eq!(texts, ["rally", "btc's", "surge", "why", "🚀"]);
+        assert_eq!((exclamations, questions), (2, 1));
+        assert!(words[0].clause < words[1].clause);
+        assert!(words[2].shouting && !words[1].shouting && !words[0].shouting);
+    }
+
+    #[test]
+    fn builtin_english_lexicon_handles_vader_rules() {
+        // Пунктуация не мешает совпадению, отрицание меняет знак
+        assert!(score("Bitcoin rally, ETF inflows surge") > 0.5);
+        assert!(score("Bitcoin did not rally this week") < 0.0);
+        assert!(score("Bitcoin very sharply rallied") > score("Bitcoin rallied"));
+        assert!(score("Bitcoin rallied!!!") > score("Bitcoin rallied"));
+        assert!(score("Bitcoin RALLIED today") > score("Bitcoin rallied today"));
+        assert!(score("Bitcoin hits an all time high 🚀") > score("Bitcoin hits a high"));
+        assert!(score("Exchange hacked, but withdrawals recover quickly") > 0.0);
+        assert!(score("Miners report record losses amid crash") < -0.5);
+        assert_eq!(score("The block was mined at noon"), 0.0);
+    }
+
+    #[test]
+    fn empty_text_is_neutral() {
+        for text in ["", "   ", "?!.,"] {
+            let result = LexiconSet::load(None).analyze(text, Some("en"));
+            assert_eq!(result.compound, 0.0, "{:?}", text);
+            assert!(!result.is_positive() && !result.is_negative());
+            assert_eq!(result.confidence(), 1.0);
+        }
+    }
+
+    #[test]
+    fn languages_without_lexicon_fall_back_to_english() {
+        let lexicons = LexiconSet::load(None);
+        assert!(lexicons.analyze("Биткоин не вырос, биржа сообщила о взломе", Some("ru")).is_negative());
+        assert_eq!(lexicons.analyze("Bitcoin rally", Some("xx")), lexicons.analyze("Bitcoin rally", None));
+        assert!(lexicons.analyze("Bitcoin rally", Some("xx")).is_positive());
+    }
+
+    #[test]
+    fn custom_lexicon_supports_directives_stems_and_phrases() {
+        let lexicon = Lexicon::parse("# комментарий\n\nwagmi\t2.0\nmoon*\t1.5\nrug pull\t-3.0\n@negation\tnot\n@booster\tsuper\t0.3\n").unwrap();
+        assert!(lexicon.analyze("not wagmi").compound < 0.0);
+        assert!(lexicon.analyze("mooning").is_positive());
+        assert!(lexicon.analyze("another rug pull").is_negative());
+        assert!(lexicon.analyze("super wagmi").compound > lexicon.analyze("wagmi").compound);
+    }
+
+    #[test]
+    fn malformed_lexicon_lines_are_rejected() {
+        assert!(Lexicon::parse("broken line without valence").is_err());
+        assert!(Lexicon::parse("wagmi\tlots").is_err());
+        assert!(Lexicon::parse("@booster\tsuper").is_err());
+        assert!(Lexicon::parse("@negation").is_err());
+        let error = Lexicon::parse("ok\t1.0\nbroken").unwrap_err().to_string();
+        assert!(error.contains("строка словаря 2"), "{}", error);
+    }
+}
@@ -0,0 +1,130 @@
+pub mod chunking;
+pub mod lexicon;
+#[cfg(feature = "onnx")]
+pub mod onnx;
+
+pub use chunking::{estimate_tokens, split_into_windows, TextWindow};
+pub use lexicon::{Lexicon, LexiconScore, LexiconSet};
+#[cfg(feature = "onnx")]
+pub use onnx::OnnxSentimentModel;
+
+/// Итог анализа настроения одной новости, независимо от движка
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SentimentPrediction {
+    pub is_positive: Option<bool>, // None — нейтральная
+    pub score: f64, // -1.0 .. 1.0
+    pub confidence: Option<f64>,
+}
+
+impl SentimentPrediction {
+    pub fn neutral() -> Self {
+        SentimentPrediction {
+            is_positive: None,
+            score: 0.0,
+            confidence: None,
+        }
+    }
+
+    /// Из меток классификатора: score = P(positive) - P(negative), направление — по самой вероятной метке
+    pub fn from_labels(labels: &[(String, f64)]) -> Option<Self> {
+        let (best_label, best_score) = labels.iter().max_by(|a, b| a.1.total_cmp(&b.1))?;
+        let probability = |name: &str| {
+            labels.iter()
+                .filter(|(label, _)| label.contains(name))
+                .map(|(_, p)| *p)
+                .sum::<f64>()
+        };
+
+        Some(SentimentPrediction {
+            is_positive: if best_label.contains("positive") {
+                Some(true)
+            } else if best_label.contains("negative") {
+                Some(false)
+            } else {
+                None
+            },
+            score: (probability("positive") - probability("negative")).clamp(-1.0, 1.0),
+            confidence: Some(*best_score),
+        })
+    }
+}
+
+impl SentimentPrediction {
+    /// Сводная оценка статьи по окнам, веса — длина окна в токенах.
+    /// Статья позитивна (негативна), если на позитивные (негативные) окна приходится
+    /// больше половины текста, иначе нейтральна
+    pub fn aggregate(windows: &[(SentimentPrediction, usize)]) -> Option<Self> {
+        let total: f64 = windows.iter().map(|(_, tokens)| *tokens as f64).sum();
+        if windows.is_empty() || total <= 0.0 {
+            return windows.first().map(|(prediction, _)| *prediction);
+        }
+        let weighted = |value: &dyn Fn(&SentimentPrediction) -> f64| {
+            windows.iter().map(|(p, tokens)| value(p) * *tokens as f64).sum::<f64>() / total
+        };
+
+        let confidences: Vec<_> = windows.iter()
+            .filter_map(|(p, tokens)| Some((p.confidence?, *tokens as f64)))
+            .collect();
+        let confidence_weight: f64 = confidences.iter().map(|(_, w)| w).sum();
+
+        Some(SentimentPrediction {
+            is_positive: if weighted(&|p| if p.is_positive == Some(true) { 1.0 } else { 0.0 }) > 0.5 {
+                Some(true)
+            } else if weighted(&|p| if p.is_positive == Some(false) { 1.0 } else { 0.0 }) > 0.5 {
+                Some(false)
+            } else {
+                None
+            },
+            score: weighted(&|p| p.score).clamp(-1.0, 1.0),
+            confidence: (confidence_weight > 0.0)
+                .then(|| confidences.iter().map(|(c, w)| c * w).sum::<f64>() / confidence_weight),
+        })
+    }
+}
+
+impl From<LexiconScore> for SentimentPrediction {
+    fn from(score: LexiconScore) -> Self {
+        SentimentPrediction {
+            is_positive: if score.is_positive() {
+                Some(true)
+            } else if score.is_negative() {
+                Some(false)
+            } else {
+                None
+            },
+            score: score.compound,
+            confidence: Some(score.confidence()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POSITIVE: SentimentPrediction = SentimentPrediction { is_positive: Some(true), score: 0.8, confidence: Some(0.9) };
+    const NEGATIVE: SentimentPrediction = SentimentPrediction { is_positive: Some(false), score: -0.4, confidence: Some(0.6) };
+
+    #[test]
+    fn aggregate_weights_windows_by_length() {
+        let aggregated = SentimentPrediction::aggregate(&[(POSITIVE, 300), (NEGATIVE, 100)]).unwrap();
+        assert_eq!(aggregated.is_positive, Some(true));
+        assert!((aggregated.score - 0.5).abs() < 1e-9);
+        assert!((aggregated.confidence.unwrap() - 0.825).abs() < 1e-9);
+    }
+
+    #[test]
+    fn aggregate_without_majority_is_neutral() {
+        let mixed = SentimentPrediction::aggregate(&[(POSITIVE, 100), (NEGATIVE, 100), (SentimentPrediction::neutral(), 100)]).unwrap();
+        assert_eq!(mixed.is_positive, None);
+        // Окна без уверенности не влияют на сводную уверенность
+        assert!((mixed.confidence.unwrap() - 0.75).abs() < 1e-9);
+    }
+
+    #[test]
+    fn aggregate_of_empty_or_zero_length_windows() {
+        assert!(SentimentPrediction::aggregate(&[]).is_none());
+        assert_eq!(SentimentPrediction::aggregate(&[(NEGATIVE, 0), (POSITIVE, 0)]), Some(NEGATIVE));
+        assert_eq!(SentimentPrediction::aggregate(&[(SentimentPrediction::neutral(), 10)]).unwrap().confidence, None);
+    }
+}
@@ -0,0 +1,115 @@
+// Локальный инференс модели настроений через ONNX Runtime (feature `onnx`)
+use std::path::Path;
+use std::sync::Mutex;
+use ort::session::{builder::GraphOptimizationLevel, Session};
+use ort::value::Tensor;
+use tokenizers::{Encoding, Tokenizer, TruncationParams};
+use crate::config::{ChunkingConfig, OnnxConfig};
+use crate::errors::{BitcoinAnalysisError, Result};
+use super::SentimentPrediction;
+
+/// Классификатор последовательностей (RoBERTa/BERT), экспортированный в ONNX
+pub struct OnnxSentimentModel {
+    session: Mutex<Session>, // Session::run требует &mut
+    tokenizer: Tokenizer,
+    input_names: Vec<String>,
+    labels: Vec<String>,
+    max_windows: usize,
+}
+
+impl OnnxSentimentModel {
+    pub fn load(config: &OnnxConfig, chunking: &ChunkingConfig) -> Result<Self> {
+        let model_path = config.model_path.as_deref()
+            .ok_or_else(|| model_error("не задан путь к модели"))?;
+        let tokenizer_path = config.tokenizer_path()
+            .ok_or_else(|| model_error("не задан путь к токенизатору"))?;
+
+        let mut tokenizer = Tokenizer::from_file(&tokenizer_path)
+            .map_err(|e| model_error(format!("{}: {}", tokenizer_path.display(), e)))?;
+        // Длинный текст токенизатор режет на окна с перекрытием (overflowing encodings)
+        tokenizer
+            .with_truncation(Some(TruncationParams {
+                max_length: chunking.max_tokens,
+                stride: chunking.overlap_tokens,
+                ..Default::default()
+            }))
+            .map_err(model_error)?;
+        tokenizer.with_padding(None);
+
+        let session = Session::builder()
+            .and_then(|builder| builder.with_optimization_level(GraphOptimizationLevel::Level3))
+            .and_then(|builder| builder.with_intra_threads(config.intra_threads))
+            .and_then(|builder| builder.commit_from_file(Path::new(model_path)))
+            .map_err(|e| model_error(format!("{}: {}", model_path, e)))?;
+        let input_names = session.inputs.iter().map(|input| input.name.clone()).collect();
+
+        tracing::info!("Загружена ONNX-модель настроений: {}", model_path);
+        Ok(OnnxSentimentModel {
+            session: Mutex::new(session),
+            tokenizer,
+            input_names,
+            labels: config.labels.iter().map(|label| label.to_lowercase()).collect(),
+            max_windows: chunking.max_windows,
+        })
+    }
+
+    /// Синхронный инференс на CPU; из async-кода вызывать через spawn_blocking.
+    /// Оценки окон длинного текста усредняются с весом по числу токенов
+    pub fn predict(&self, text: &str) -> Result<SentimentPrediction> {
+        let mut encoding = self.tokenizer.encode(text, true).map_err(model_error)?;
+        let overflowing = encoding.take_overflowing();
+
+        let mut windows = Vec::new();
+        for window in std::iter::once(encoding).chain(overflowing).take(self.max_windows) {
+            windows.push((self.predict_window(&window)?, window.get_ids().len()));
+        }
+        SentimentPrediction::aggregate(&windows).ok_or_else(|| model_error("пустой ответ модели"))
+    }
+
+    fn predict_window(&self, encoding: &Encoding) -> Result<SentimentPrediction> {
+        let len = encoding.get_ids().len();
+        let to_i64 = |values: &[u32]| values.iter().map(|&v| v as i64).collect::<Vec<_>>();
+
+        let mut inputs = Vec::with_capacity(self.input_names.len());
+        for name in &self.input_names {
+            let values = match name.as_str() {
+                "input_ids" => to_i64(encoding.get_ids()),
+                "attention_mask" => to_i64(encoding.get_attention_mask()),
+                "token_type_ids" => to_i64(encoding.get_type_ids()),
+                other => return Err(model_error(format!("неизвестный вход модели: {}", other))),
+            };
+            let tensor = Tensor::from_array(([1usize, len], values)).map_err(model_error)?;
+            inputs.push((name.clone(), tensor));
+        }
+
+        let logits = {
+            let mut session = self.session.lock()
+                .map_err(|_| model_error("сессия ONNX повреждена"))?;
+            let outputs = session.run(inputs).map_err(model_error)?;
+            let (_, logits) = outputs[0].try_extract_tensor::<f32>().map_err(model_error)?;
+            logits.to_vec()
+        };
+
+        if logits.len() != self.labels.len() {
+            return Err(model_error(format!(
+                "модель вернула {} классов, а в конфигурации {} меток",
+                logits.len(),
+                self.labels.len()
+            )));
+        }
+
+        let labels: Vec<(String, f64)> = self.labels.iter().cloned().zip(softmax(&logits)).collect();
+        SentimentPrediction::from_labels(&labels).ok_or_else(|| model_error("пустой ответ модели"))
+    }
+}
+
+fn softmax(logits: &[f32]) -> Vec<f64> {
+    let max = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max) as f64;
+    let exps: Vec<f64> = logits.iter().map(|&x| (x as f64 - max).exp()).collect();
+    let sum: f64 = exps.iter().sum();
+    exps.into_iter().map(|e| e / sum).collect()
+}
+
+fn model_error(error: impl ToString) -> BitcoinAnalysisError {
+    BitcoinAnalysisError::ModelError(error.to_string())
+}
@@ -0,0 +1,104 @@
+use futures::stream::{self, StreamExt};
+use regex::Regex;
+use reqwest::{header, Client, ClientBuilder};
+use std::sync::OnceLock;
+use std::time::Duration;
+
+use crate::analytics::extract_main_text;
+use crate::config::ArticleFetchConfig;
+use crate::errors::{BitcoinAnalysisError, Result};
+use crate::models::{BitcoinNews, SourceKind};
+
+/// Загрузка полных текстов статей по ссылкам из NewsAPI и RSS (секция `[article_fetch]`)
+#[derive(Clone)]
+pub struct ArticleFetcher {
+    client: Client,
+    config: ArticleFetchConfig,
+}
+
+impl ArticleFetcher {
+    pub fn new(config: ArticleFetchConfig) -> Self {
+        let client = ClientBuilder::new()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .user_agent(config.user_agent.clone())
+            .build()
+            .expect("Failed to build reqwest client");
+        ArticleFetcher { client, config }
+    }
+
+    /// Заполняет `full_text` у новостей с обрезанным или коротким текстом.
+    /// Посты соцсетей не загружаются: ссылка ведёт на обсуждение, а не на статью.
+    /// Ошибки загрузки не прерывают сбор: у такой новости остаётся исходный фрагмент
+    pub async fn enrich(&self, news_items: &mut [BitcoinNews]) -> usize {
+        let tasks: Vec<_> = news_items.iter()
+            .enumerate()
+            .filter(|(_, news)| news.source_kind == SourceKind::Editorial)
+            .filter(|(_, news)| news.full_text.is_none() && self.needs_full_text(&news.content))
+            .filter_map(|(index, news)| Some((index, news.url.clone()?)))
+            .map(|(index, url)| async move { (index, self.fetch_text(&url).await, url) })
+            .collect();
+        tracing::info!("Загрузка полного текста для {} новостей", tasks.len());
+
+        let results = stream::iter(tasks)
+            .buffer_unordered(self.config.max_concurrent)
+            .collect::<Vec<_>>()
+            .await;
+
+        let mut fetched = 0;
+        for (index, result, url) in results {
+            match result {
+                Ok(Some(text)) => {
+                    news_items[index].full_text = Some(text);
+                    fetched += 1;
+                }
+                Ok(None) => tracing::debug!("Не удалось выделить текст статьи: {}", url),
+                Err(e) => tracing::debug!("Ошибка загрузки статьи {}: {}", url, e),
+            }
+        }
+        tracing::info!("Загружен полный текст {} новостей", fetched);
+        fetched
+    }
+
+    /// Фрагмент NewsAPI обрезан маркером "[+N chars]", у RSS часто есть только анонс
+    fn needs_full_text(&self, content: &str) -> bool {
+        truncation_marker().is_match(content) || content.chars().count() < self.config.snippet_threshold_chars
+    }
+
+    async fn fetch_text(&self, url: &str) -> Result<Option<String>> {
+        let mut response = self.client.get(url).send().await?;
+        if !response.status().is_success() {
+            return Err(BitcoinAnalysisError::ApiError(format!("статус {}", response.status())));
+        }
+
+        let is_html = response.headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .is_none_or(|value| value.contains("html"));
+        if !is_html {
+            return Ok(None);
+        }
+
+        // Читаем не больше max_bytes: у длинной страницы статья обычно в начале
+        let mut body = Vec::new();
+        while let Some(chunk) = response.chunk().await? {
+            body.extend_from_slice(&chunk);
+            if body.len() >= self.config.max_bytes {
+                body.truncate(self.config.max_bytes);
+                break;
+            }
+        }
+
+        let html = String::from_utf8_lossy(&body);
+        Ok(extract_main_text(&html)
+            .filter(|text| text.chars().count() >= self.config.min_text_chars)
+            .map(|text| match text.char_indices().nth(self.config.max_text_chars) {
+                Some((end, _)) => text[..end].to_string(),
+                None => text,
+            }))
+    }
+}
+
+fn truncation_marker() -> &'static Regex {
+    static MARKER: OnceLock<Regex> = OnceLock::new();
+    MARKER.get_or_init(|| Regex::new(r"\[\+\d+ chars\]").expect("valid regex"))
+}
@@ -0,0 +1,168 @@
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use chrono::Utc;
+
+use crate::config::AppConfig;
+use crate::errors::{BitcoinAnalysisError, Result};
+use crate::holders::{BitcoinNewsHolder, BitcoinPriceHolder};
+use crate::models::{AmountDays, BacktestParams, BacktestReport, BacktestStep, BitcoinNews, BitcoinPrice};
+use crate::services::DataMakerDecisionService;
+
+const TRADING_DAYS_PER_YEAR: f64 = 365.0;
+
+#[derive(Clone)]
+pub struct BacktestService {
+    price_holder: BitcoinPriceHolder,
+    news_holder: BitcoinNewsHolder,
+    config: AppConfig,
+}
+
+impl BacktestService {
+    pub fn new(price_holder: BitcoinPriceHolder, news_holder: BitcoinNewsHolder, config: AppConfig) -> Self {
+        BacktestService {
+            price_holder,
+            news_holder,
+            config,
+        }
+    }
+
+    /// Бэктест на данных, сохраненных последним сбором
+    pub async fn run_on_stored(&self, params: &BacktestParams) -> Result<BacktestReport> {
+        let prices = self.price_holder.get().await?;
+        let news_items = self.news_holder.get().await?;
+        self.run(prices, news_items, params).await
+    }
+
+    /// Прогоняет историю день за днем через сервис принятия решений и симулирует стратегию
+    /// long/flat/short по сигналу `market_sentiment`. Новости должны быть уже размечены.
+    pub async fn run(&self, mut prices: Vec<BitcoinPrice>, news_items: Vec<BitcoinNews>, params: &BacktestParams) -> Result<BacktestReport> {
+        // Нулевая или битая цена (например, из CSV) дала бы бесконечную доходность шага
+        let total_prices = prices.len();
+        prices.retain(|p| p.price.is_finite() && p.price > 0.0);
+        if prices.len() < total_prices {
+            tracing::warn!("Бэктест: пропущено {} некорректных цен", total_prices - prices.len());
+        }
+        prices.sort_by_key(|p| p.date);
+        prices.dedup_by_key(|p| p.date);
+
+        // Новости с датой по возрастанию: новости окна каждого шага — непрерывный срез
+        let mut news_items: Vec<BitcoinNews> = news_items.into_iter().filter(|n| n.published_date().is_some()).collect();
+        news_items.sort_by_key(|n| n.published_date());
+
+        let window = params.window_days.max(1) as usize;
+        if prices.len() < window + 2 {
+            return Err(BitcoinAnalysisError::InvalidDataFormat(format!(
+                "Недостаточно данных для бэктеста: {} цен при окне {} дней",
+                prices.len(),
+                window
+            )));
+        }
+
+        // Стратегия читает только market_sentiment, поэтому остальной анализ по шагам не строится
+        let decision = DataMakerDecisionService::new(
+            BitcoinPriceHolder::new(),
+            BitcoinNewsHolder::new(),
+            Arc::new(Mutex::new(AmountDays { days: params.window_days })),
+            self.config.clone(),
+        );
+
+        let fee_rate = params.fee_bps / 10_000.0;
+        let mut position: f64 = 0.0;
+        let mut equity = 1.0;
+        let mut peak_equity = 1.0;
+        let mut max_drawdown: f64 = 0.0;
+        let mut liquidated = false;
+        let mut trades = 0;
+        let mut hits = 0;
+        let mut directional_steps = 0;
+        let mut strategy_returns = Vec::new();
+        let mut steps = Vec::new();
+
+        // Сигнал дня t строится по окну цен [t - window, t] и новостям, опубликованным до конца дня t
+        for t in window..prices.len() - 1 {
+            let window_start = prices[t - window].date;
+            let signal_date = prices[t].date;
+
+            let news_from = news_items.partition_point(|n| n.published_date() <= Some(window_start));
+            let news_to = news_items.partition_point(|n| n.published_date() <= Some(signal_date));
+            let signal = decision.market_sentiment(
+                &prices[t - window..=t],
+                &news_items[news_from..news_to],
+                &self.config.decision,
+            )?;
+            let target_position = match signal.as_str() {
+                "very_bullish" | "bullish" => 1.0,
+                "very_bearish" | "bearish" if params.allow_short => -1.0,
+                _ => 0.0,
+            };
+
+            let fee = (target_position - position).abs() * fee_rate;
+            if target_position != position {
+                trades += 1;
+            }
+            position = target_position;
+
+            let market_return = prices[t + 1].price / prices[t].price - 1.0;
+            let strategy_return = position * market_return - fee;
+            if position != 0.0 && market_return != 0.0 {
+                directional_steps += 1;
+                if position * market_return > 0.0 {
+                    hits += 1;
+                }
+            }
+
+            // Шорт при росте больше 100% теряет больше капитала: счет обнуляется, торговля прекращается
+            equity = (equity * (1.0 + strategy_return)).max(0.0);
+            peak_equity = f64::max(peak_equity, equity);
+            max_drawdown = max_drawdown.max((peak_equity - equity) / peak_equity * 100.0);
+            strategy_returns.push(strategy_return);
+
+            steps.push(BacktestStep {
+                date: signal_date,
+                signal,
+                position,
+                market_return: market_return * 100.0,
+                strategy_return: strategy_return * 100.0,
+                equity,
+            });
+
+            if equity == 0.0 {
+                tracing::warn!("Бэктест: капитал исчерпан {}, симуляция остановлена", prices[t + 1].date);
+                liquidated = true;
+                break;
+            }
+        }
+
+        let periods = strategy_returns.len() as f64;
+        let mean_return = strategy_returns.iter().sum::<f64>() / periods;
+        let std_return = if strategy_returns.len() > 1 {
+            (strategy_returns.iter().map(|r| (r - mean_return).powi(2)).sum::<f64>() / (periods - 1.0)).sqrt()
+        } else {
+            0.0
+        };
+        let sharpe_ratio = (std_return > 0.0)
+            .then(|| mean_return / std_return * TRADING_DAYS_PER_YEAR.sqrt());
+
+        let first_traded = prices[window].price;
+        // После ликвидации отчет заканчивается на дне, когда капитал обнулился
+        let last_index = window + steps.len();
+        let last_price = prices[last_index].price;
+
+        Ok(BacktestReport {
+            timestamp: Utc::now().to_rfc3339(),
+            start_date: prices[window].date,
+            end_date: prices[last_index].date,
+            params: params.clone(),
+            steps_count: steps.len(),
+            trades,
+            hit_rate: (directional_steps > 0).then(|| hits as f64 / directional_steps as f64),
+            total_return: (equity - 1.0) * 100.0,
+            annualized_return: (equity.powf(TRADING_DAYS_PER_YEAR / periods) - 1.0) * 100.0,
+            liquidated,
+            buy_and_hold_return: (last_price / first_traded - 1.0) * 100.0,
+            sharpe_ratio,
+            max_drawdown,
+            steps,
+        })
+    }
+}
@@ -1,14 +1,22 @@
-use chrono::{Days, Utc};
-use regex::Regex;
-use reqwest::Client;
+use chrono::{DateTime, Days, NaiveDate, Utc};
+use reqwest::{Client, ClientBuilder};
 use serde_json::Value;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 
-use crate::config::AppConfig;
+use crate::analytics::{detect_language, QueryMatcher};
+use crate::config::{AppConfig, JsonSourceConfig};
 use crate::errors::{BitcoinAnalysisError, Result};
 use crate::holders::{BitcoinNewsHolder, BitcoinPriceHolder};
-use crate::models::{AmountDays, BitcoinNews, BitcoinPrice};
+use crate::models::{AmountDays, BitcoinNews, BitcoinPrice, NewsApiUsage, SourceKind};
+use crate::services::{ArticleFetcher, CsvPriceSource, FileNewsSource, GdeltSource, JsonNewsSource};
+
+// Ответ NewsAPI на запрос страницы
+enum NewsApiPage {
+    Articles { articles: Vec<Value>, total_results: usize },
+    // Тариф не отдаёт результаты дальше этой страницы
+    MaximumResultsReached,
+}
 
 #[derive(Clone)]
 pub struct DataCollectorService {
@@ -17,6 +25,8 @@ pub struct DataCollectorService {
     news_holder: BitcoinNewsHolder,
     amount_days: Arc<Mutex<AmountDays>>,
     config: AppConfig,
+    newsapi_usage: Arc<Mutex<Option<NewsApiUsage>>>,
+    newsapi_requests: Arc<Mutex<(NaiveDate, usize)>>, // запросы к NewsAPI за сутки UTC
 }
 
 impl DataCollectorService {
@@ -32,12 +42,20 @@ impl DataCollectorService {
             news_holder,
             amount_days,
             config,
+            newsapi_usage: Arc::new(Mutex::new(None)),
+            newsapi_requests: Arc::new(Mutex::new((Utc::now().date_naive(), 0))),
         }
     }
 
+    /// Использование NewsAPI при последнем сборе новостей
+    pub async fn newsapi_usage(&self) -> Option<NewsApiUsage> {
+        self.newsapi_usage.lock().await.clone()
+    }
+
     pub async fn collect_data(&self) -> Result<()> {
         self.price_holder.clear().await?;
         self.news_holder.clear().await?;
+        *self.newsapi_usage.lock().await = None;
 
         let price_task = {
             let service = self.clone();
@@ -57,6 +75,10 @@ impl DataCollectorService {
     }
 
     async fn collect_bitcoin_prices(&self) -> Result<()> {
+        // Файл цен задан явно: сеть и синтетические данные не используются
+        if let Some(path) = &self.config.files.prices_csv {
+            return self.collect_from_price_csv(path).await;
+        }
 
         // 1. CoinGecko API
         match self.collect_from_coingecko_current().await {
@@ -81,6 +103,42 @@ impl DataCollectorService {
         self.collect_realistic_current_data().await
     }
 
+    /// Адреса запросов к API цен за последние `days` дней с базовыми адресами из `[endpoints]`
+    pub fn price_api_urls(&self, days: u32) -> Vec<(&'static str, String)> {
+        vec![
+            ("coingecko", self.coingecko_url(days)),
+            ("binance", self.binance_url(days)),
+            ("coincap", self.coincap_url(days)),
+        ]
+    }
+
+    fn coingecko_url(&self, days: u32) -> String {
+        format!(
+            "{}/coins/bitcoin/market_chart?vs_currency=usd&days={}&interval=daily",
+            self.config.endpoints.coingecko_url.trim_end_matches('/'),
+            days
+        )
+    }
+
+    fn binance_url(&self, days: u32) -> String {
+        format!(
+            "{}/klines?symbol=BTCUSDT&interval=1d&limit={}",
+            self.config.endpoints.binance_url.trim_end_matches('/'),
+            days
+        )
+    }
+
+    fn coincap_url(&self, days: u32) -> String {
+        let end_timestamp = Utc::now().timestamp() * 1000;
+        let start_timestamp = end_timestamp - (days as i64 * 24 * 60 * 60 * 1000);
+        format!(
+            "{}/assets/bitcoin/history?interval=d1&start={}&end={}",
+            self.config.endpoints.coincap_url.trim_end_matches('/'),
+            start_timestamp,
+            end_timestamp
+        )
+    }
+
     async fn collect_from_coingecko_current(&self) -> Result<()> {
         let days = {
             let amount_days = self.amount_days.lock().await;
@@ -95,10 +153,7 @@ impl DataCollectorService {
             })?;
 
         // CoinGecko API с актуальными данными
-        let url = format!(
-            "https://api.coingecko.com/api/v3/coins/bitcoin/market_chart?vs_currency=usd&days={}&interval=daily",
-            days
-        );
+        let url = self.coingecko_url(days);
 
         tracing::info!("Получение актуальных данных CoinGecko за {} дней", days);
         tracing::debug!("URL: {}", url);
@@ -180,10 +235,7 @@ impl DataCollectorService {
         };
 
         // Binance Klines API для получения дневных данных
-        let url = format!(
-            "https://api.binance.com/api/v3/klines?symbol=BTCUSDT&interval=1d&limit={}",
-            days
-        );
+        let url = self.binance_url(days);
 
         tracing::info!("Получение данных из Binance за {} дней", days);
 
@@ -262,13 +314,7 @@ impl DataCollectorService {
         };
 
         // CoinCap API для исторических данных
-        let end_timestamp = Utc::now().timestamp() * 1000;
-        let start_timestamp = end_timestamp - (days as i64 * 24 * 60 * 60 * 1000);
-
-        let url = format!(
-            "https://api.coincap.io/v2/assets/bitcoin/history?interval=d1&start={}&end={}",
-            start_timestamp, end_timestamp
-        );
+        let url = self.coincap_url(days);
 
         tracing::info!("Получение данных из CoinCap за {} дней", days);
 
@@ -365,30 +411,66 @@ impl DataCollectorService {
         Ok(())
     }
 
-    async fn collect_bitcoin_news(&self) -> Result<()> {
-        // Пытаемся использовать реальный NewsAPI (ключ загружается из .env)
-        match self.collect_from_newsapi().await {
-            Ok(_) => {
-                tracing::info!("Успешно собраны новости через NewsAPI");
-                return Ok(());
-            },
-            Err(e) => {
-                tracing::warn!("NewsAPI недоступен: {}", e);
-                tracing::info!("Переходим к резервным источникам новостей");
+    /// Сбор новостей: NewsAPI, при его недоступности — RSS; посты Reddit добавляются к ним
+    pub async fn collect_bitcoin_news(&self) -> Result<()> {
+        let editorial = self.collect_editorial_news().await;
+
+        let social_count = if self.config.reddit.enabled {
+            match self.collect_from_reddit().await {
+                Ok(count) => {
+                    tracing::info!("Собрано {} постов Reddit", count);
+                    count
+                }
+                Err(e) => {
+                    tracing::warn!("Reddit недоступен: {}", e);
+                    0
+                }
+            }
+        } else {
+            0
+        };
+
+        match editorial {
+            Ok(()) => {}
+            Err(e) if social_count > 0 => {
+                tracing::warn!("Редакционные источники недоступны, анализируются только посты Reddit: {}", e);
             }
+            Err(e) => return Err(e),
         }
+        self.fetch_full_articles().await
+    }
 
-        // Пытаемся использовать RSS фиды как резервный источник
-        match self.collect_from_rss_feeds().await {
-            Ok(_) => {
-                tracing::info!("Успешно собраны новости через RSS фиды");
-                return Ok(());
-            },
-            Err(e) => {
-                tracing::warn!("RSS фиды недоступны: {}", e);
+    /// Редакционные источники в порядке `[news_sources] order`: до первого, вернувшего новости,
+    /// или все, если `combine = true`
+    async fn collect_editorial_news(&self) -> Result<()> {
+        let mut any_success = false;
+        for source in &self.config.news_sources.order {
+            let result = match source.as_str() {
+                "newsapi" => self.collect_from_newsapi().await,
+                "rss" => self.collect_from_rss_feeds().await,
+                "gdelt" => self.collect_from_gdelt().await,
+                "files" => self.collect_from_files().await,
+                name => match self.config.json_sources.iter().find(|s| s.name == name) {
+                    Some(config) => self.collect_from_json_source(config).await,
+                    None => Err(BitcoinAnalysisError::NoDataSourcesAvailable(format!("неизвестный источник {}", name))),
+                },
+            };
+            match result {
+                Ok(()) => {
+                    tracing::info!("Успешно собраны новости через {}", source);
+                    any_success = true;
+                    if !self.config.news_sources.combine {
+                        break;
+                    }
+                }
+                Err(e) => tracing::warn!("Источник {} недоступен: {}", source, e),
             }
         }
 
+        if any_success {
+            return Ok(());
+        }
+
         // Все источники новостей недоступны
         tracing::error!("Все источники новостей недоступны");
         Err(BitcoinAnalysisError::NoDataSourcesAvailable(
@@ -396,97 +478,339 @@ impl DataCollectorService {
         ))
     }
 
+    async fn collect_from_gdelt(&self) -> Result<()> {
+        let days = self.amount_days.lock().await.days;
+        // Запрос применяется на стороне GDELT к полному тексту статей; локально доступен только заголовок
+        let query = self.config.query.for_source("gdelt", &self.config.bitcoin_keywords)?;
+        let news_items = GdeltSource::new(self.config.gdelt.clone()).fetch(&query.to_gdelt(), days).await?;
+        self.add_source_news("GDELT", news_items, None).await
+    }
+
+    /// Новости из архива `[files] news_directory` за период, заканчивающийся `end_date`
+    /// или самой поздней датой архива; новости без даты не отбрасываются, но идут последними
+    async fn collect_from_files(&self) -> Result<()> {
+        let days = self.amount_days.lock().await.days;
+        let source = FileNewsSource::new(self.config.files.clone());
+        let mut news_items = tokio::task::spawn_blocking(move || source.read()).await??;
+
+        let published_date = |news: &BitcoinNews| news.published_datetime().map(|datetime| datetime.date_naive());
+        if let Some(end_date) = self.config.files.end_date.or_else(|| news_items.iter().filter_map(published_date).max()) {
+            let start_date = period_start(end_date, days)?;
+            news_items.retain(|news| published_date(news).is_none_or(|date| date > start_date && date <= end_date));
+        }
+        // Свежие новости первыми, чтобы max_articles отсекал самые старые; новости без даты — в конце
+        news_items.sort_by_key(|news| std::cmp::Reverse(news.published_datetime()));
+
+        let query = self.config.query.for_source("files", &self.config.bitcoin_keywords)?;
+        self.add_source_news("files", news_items, Some(&query.matcher()?)).await
+    }
+
+    /// Дневные цены из CSV `[files] prices_csv` за период, заканчивающийся `end_date`
+    /// или последней датой файла
+    async fn collect_from_price_csv(&self, path: &str) -> Result<()> {
+        let days = self.amount_days.lock().await.days;
+        let source = CsvPriceSource::new(path, self.config.files.max_file_bytes);
+        let prices = tokio::task::spawn_blocking(move || source.read()).await??;
+
+        let end_date = self.config.files.end_date
+            .or_else(|| prices.last().map(|price| price.date))
+            .ok_or(BitcoinAnalysisError::PriceDataUnavailable)?;
+        let start_date = period_start(end_date, days)?;
+
+        let mut added_count = 0;
+        for price in prices.into_iter().filter(|price| price.date > start_date && price.date <= end_date) {
+            self.price_holder.add(price).await?;
+            added_count += 1;
+        }
+
+        tracing::info!("Загружено {} цен из {} ({} — {})", added_count, path, start_date, end_date);
+        if added_count == 0 {
+            return Err(BitcoinAnalysisError::PriceDataUnavailable);
+        }
+        Ok(())
+    }
+
+    async fn collect_from_json_source(&self, config: &JsonSourceConfig) -> Result<()> {
+        let days = self.amount_days.lock().await.days;
+        let from_date = Utc::now()
+            .date_naive()
+            .checked_sub_days(Days::new(days as u64))
+            .ok_or_else(|| {
+                BitcoinAnalysisError::InvalidDataFormat("Невозможно вычислить дату".to_string())
+            })?;
+        let query = self.config.query.for_source(&config.name, &self.config.bitcoin_keywords)?;
+        let news_items = JsonNewsSource::new(config.clone())?
+            .fetch(&query.to_newsapi(), from_date, days)
+            .await?;
+        self.add_source_news(&config.name, news_items, Some(&query.matcher()?)).await
+    }
+
+    /// Добавляет новости источника, прошедшие фильтр запроса; пустой результат — ошибка,
+    /// чтобы сбор перешёл к следующему источнику
+    async fn add_source_news(&self, source: &str, news_items: Vec<BitcoinNews>, matcher: Option<&QueryMatcher>) -> Result<()> {
+        let max_articles = self.config.max_articles.unwrap_or(50);
+        let mut added_count = 0;
+        for news in news_items {
+            if added_count >= max_articles {
+                break;
+            }
+            if matcher.is_some_and(|m| !m.is_match(&format!("{} {}", news.title, news.content))) {
+                continue;
+            }
+            self.news_holder.add(news).await?;
+            added_count += 1;
+        }
+
+        tracing::info!("Собрано {} новостей из {}", added_count, source);
+        if added_count == 0 {
+            return Err(BitcoinAnalysisError::NoDataSourcesAvailable(format!("{} не вернул новостей", source)));
+        }
+        Ok(())
+    }
+
+    /// Дополняет собранные новости полным текстом статей, если это включено в конфигурации
+    async fn fetch_full_articles(&self) -> Result<()> {
+        if !self.config.article_fetch.enabled {
+            return Ok(());
+        }
+
+        let mut news_items = self.news_holder.get().await?;
+        ArticleFetcher::new(self.config.article_fetch.clone()).enrich(&mut news_items).await;
+
+        self.news_holder.clear().await?;
+        for news in news_items {
+            self.news_holder.add(news).await?;
+        }
+        Ok(())
+    }
+
     async fn collect_from_newsapi(&self) -> Result<()> {
+        let mut usage = NewsApiUsage::default();
+        let result = self.collect_newsapi_pages(&mut usage).await;
+        tracing::info!(
+            "NewsAPI: запросов {} (за сутки {}, осталось {}), totalResults {}, получено статей {}",
+            usage.requests_made,
+            usage.requests_today,
+            usage.requests_remaining.map_or_else(|| "без ограничения".to_string(), |r| r.to_string()),
+            usage.total_results,
+            usage.articles_received
+        );
+        *self.newsapi_usage.lock().await = Some(usage);
+        result
+    }
+
+    async fn collect_newsapi_pages(&self, usage: &mut NewsApiUsage) -> Result<()> {
         let days = {
             let amount_days = self.amount_days.lock().await;
             amount_days.days
         };
 
+        // Тариф ограничивает глубину архива: запрос за больший период NewsAPI отклонит
+        let newsapi = &self.config.newsapi;
+        usage.history_days = days;
+        if newsapi.max_history_days > 0 && days > newsapi.max_history_days {
+            tracing::warn!("Период новостей сокращён с {} до {} дней ограничением тарифа NewsAPI", days, newsapi.max_history_days);
+            usage.history_days = newsapi.max_history_days;
+            usage.history_limited = true;
+        }
+
         let from_date = Utc::now()
             .date_naive()
-            .checked_sub_days(Days::new(days as u64))
+            .checked_sub_days(Days::new(usage.history_days as u64))
             .ok_or_else(|| {
                 BitcoinAnalysisError::InvalidDataFormat("Невозможно вычислить дату".to_string())
             })?;
 
-        let keywords = self.config.bitcoin_keywords.join(" OR ");
         let max_articles = self.config.max_articles.unwrap_or(50);
-        
+        let query = self.config.query.for_source("newsapi", &self.config.bitcoin_keywords)?;
+        let matcher = query.matcher()?;
+        let q = query.to_newsapi();
+
+        // NewsAPI принимает один язык за запрос, поэтому запрашиваем языки по очереди
+        let languages: Vec<Option<&str>> = if self.config.languages.newsapi.is_empty() {
+            vec![None]
+        } else {
+            self.config.languages.newsapi.iter().map(|l| Some(l.as_str())).collect()
+        };
+
+        let mut added_count = 0;
+        let mut last_error = None;
+        let mut any_success = false;
+
+        'languages: for language in languages {
+            let mut page = 1;
+            while added_count < max_articles
+                && page <= newsapi.max_pages
+                && (page - 1) * newsapi.page_size < newsapi.max_results
+            {
+                if !self.reserve_newsapi_request(usage).await {
+                    tracing::warn!("Достигнут суточный лимит запросов NewsAPI ({})", newsapi.daily_request_limit);
+                    usage.stop_reason = Some("daily_request_limit".to_string());
+                    last_error = Some(BitcoinAnalysisError::RateLimited(format!(
+                        "достигнут суточный лимит {} запросов NewsAPI",
+                        newsapi.daily_request_limit
+                    )));
+                    break 'languages;
+                }
+
+                let (articles, total_results) = match self.fetch_newsapi_page(&q, language, from_date, page).await {
+                    Ok(NewsApiPage::Articles { articles, total_results }) => (articles, total_results),
+                    Ok(NewsApiPage::MaximumResultsReached) => {
+                        // Предел тарифа — штатное окончание выдачи, а не отказ источника,
+                        // даже если он сработал на первой странице
+                        tracing::info!("NewsAPI: достигнут предел результатов тарифа на странице {}", page);
+                        usage.stop_reason = Some("maximumResultsReached".to_string());
+                        any_success = true;
+                        break;
+                    }
+                    Err(BitcoinAnalysisError::RateLimited(message)) => {
+                        tracing::warn!("NewsAPI: исчерпан лимит запросов: {}", message);
+                        usage.stop_reason = Some("rateLimited".to_string());
+                        self.exhaust_newsapi_quota(usage).await;
+                        last_error = Some(BitcoinAnalysisError::RateLimited(message));
+                        break 'languages;
+                    }
+                    Err(e) => {
+                        tracing::warn!("NewsAPI ({}) недоступен: {}", language.unwrap_or("all"), e);
+                        last_error = Some(e);
+                        break;
+                    }
+                };
+                any_success = true;
+                if page == 1 {
+                    usage.total_results += total_results;
+                }
+                usage.articles_received += articles.len();
+
+                for article in &articles {
+                    if added_count >= max_articles {
+                        break;
+                    }
+                    let title = article["title"].as_str().unwrap_or("").to_string();
+                    let content = article["content"].as_str().unwrap_or("").to_string();
+                    let url = article["url"].as_str().map(|s| s.to_string());
+                    let published_at = article["publishedAt"].as_str().map(|s| s.to_string());
+                    let source = article["source"]["name"].as_str().map(|s| s.to_string());
+
+                    if matcher.is_match(&format!("{} {}", title, content)) {
+                        let language = detect_language(&format!("{} {}", title, content))
+                            .or_else(|| language.map(|l| l.to_string()));
+                        self.news_holder.add(BitcoinNews {
+                            title,
+                            content,
+                            is_positive: None,
+                            url,
+                            published_at,
+                            language,
+                            source,
+                            ..Default::default()
+                        }).await?;
+                        added_count += 1;
+                    }
+                }
+
+                // Последняя страница: статей меньше размера страницы или все totalResults получены
+                if articles.len() < newsapi.page_size || page * newsapi.page_size >= total_results {
+                    break;
+                }
+                page += 1;
+            }
+        }
+
+        if !any_success {
+            return Err(last_error.unwrap_or_else(|| {
+                BitcoinAnalysisError::ApiError("NewsAPI не вернул данных".to_string())
+            }));
+        }
+
+        tracing::info!("Собрано {} актуальных новостей Bitcoin", added_count);
+        Ok(())
+    }
+
+    /// Учитывает запрос в суточном счётчике; false — лимит тарифа на сегодня исчерпан
+    async fn reserve_newsapi_request(&self, usage: &mut NewsApiUsage) -> bool {
+        let limit = self.config.newsapi.daily_request_limit;
+        let mut counter = self.newsapi_requests.lock().await;
+        let today = Utc::now().date_naive();
+        if counter.0 != today {
+            *counter = (today, 0);
+        }
+
+        let allowed = limit == 0 || counter.1 < limit;
+        if allowed {
+            counter.1 += 1;
+            usage.requests_made += 1;
+        }
+        usage.requests_today = counter.1;
+        usage.requests_remaining = (limit > 0).then(|| limit.saturating_sub(counter.1));
+        allowed
+    }
+
+    /// NewsAPI сообщил об исчерпании лимита: до конца суток к нему не обращаемся
+    async fn exhaust_newsapi_quota(&self, usage: &mut NewsApiUsage) {
+        let limit = self.config.newsapi.daily_request_limit;
+        if limit > 0 {
+            let mut counter = self.newsapi_requests.lock().await;
+            counter.1 = counter.1.max(limit);
+            usage.requests_remaining = Some(0);
+        }
+    }
+
+    async fn fetch_newsapi_page(&self, q: &str, language: Option<&str>, from_date: NaiveDate, page: usize) -> Result<NewsApiPage> {
+        let language_param = language
+            .map(|l| format!("&language={}", l))
+            .unwrap_or_default();
+
         let url = format!(
-            "{}?q={}&from={}&language=en&sortBy=publishedAt&pageSize={}&apiKey={}",
+            "{}?q={}&from={}{}&sortBy=publishedAt&pageSize={}&page={}&apiKey={}",
             self.config.newsapi_url,
-            urlencoding::encode(&keywords),
+            urlencoding::encode(q),
             from_date.format("%Y-%m-%d"),
-            max_articles,
+            language_param,
+            self.config.newsapi.page_size,
+            page,
             self.config.newsapi_key
         );
 
-        tracing::info!("Запрос актуальных новостей с {}", from_date);
+        tracing::info!(
+            "Запрос актуальных новостей с {} (язык: {}, страница {})",
+            from_date,
+            language.unwrap_or("любой"),
+            page
+        );
 
         let response = self.client.get(&url).send().await?;
         let status = response.status();
-
-        if !status.is_success() {
-            let error_text = response
-                .text()
-                .await
-                .unwrap_or_else(|_| "Неизвестная ошибка".to_string());
-            return Err(BitcoinAnalysisError::ApiError(format!(
-                "NewsAPI error: {} - {}",
-                status, error_text
-            )));
+        let body = response.text().await?;
+        let json: Value = serde_json::from_str(&body).unwrap_or(Value::Null);
+
+        if !status.is_success() || json["status"] == "error" {
+            let message = json["message"].as_str().unwrap_or(&body).to_string();
+            return match json["code"].as_str() {
+                Some("rateLimited") => Err(BitcoinAnalysisError::RateLimited(message)),
+                Some("maximumResultsReached") => Ok(NewsApiPage::MaximumResultsReached),
+                _ => Err(BitcoinAnalysisError::ApiError(format!(
+                    "NewsAPI error: {} - {}",
+                    status, message
+                ))),
+            };
         }
 
-        let json: Value = response.json().await?;
-        let articles = json["articles"].as_array().ok_or_else(|| {
+        let articles = json["articles"].as_array().cloned().ok_or_else(|| {
             BitcoinAnalysisError::InvalidDataFormat("Отсутствует поле articles".to_string())
         })?;
-
-        let keyword_regex = Regex::new(&format!(
-            r"(?i)\b({})\b",
-            self.config.bitcoin_keywords.join("|")
-        ))?;
-        let mut added_count = 0;
-
-        for article in articles.iter().take(max_articles) {
-            let title = article["title"].as_str().unwrap_or("").to_string();
-            let content = article["content"].as_str().unwrap_or("").to_string();
-            let url = article["url"].as_str().map(|s| s.to_string());
-            let published_at = article["publishedAt"].as_str().map(|s| s.to_string());
-
-            if keyword_regex.is_match(&content) || keyword_regex.is_match(&title) {
-                self.news_holder.add(BitcoinNews {
-                    title,
-                    content,
-                    is_positive: None,
-                    url,
-                    published_at,
-                }).await?;
-                added_count += 1;
-            }
-        }
-
-        tracing::info!("Собрано {} актуальных новостей Bitcoin", added_count);
-        Ok(())
+        let total_results = json["totalResults"].as_u64().map_or(articles.len(), |total| total as usize);
+        Ok(NewsApiPage::Articles { articles, total_results })
     }
 
     async fn collect_from_rss_feeds(&self) -> Result<()> {
         tracing::info!("Сбор новостей из RSS фидов");
 
-        let rss_feeds = vec![
-            "https://cointelegraph.com/rss",
-            "https://coindesk.com/arc/outboundfeeds/rss/",
-            "https://decrypt.co/feed",
-        ];
-
         let mut total_added = 0;
-        let keyword_regex = regex::Regex::new(&format!(
-            r"(?i)\b({})\b",
-            self.config.bitcoin_keywords.join("|")
-        ))?;
+        let matcher = self.config.query.for_source("rss", &self.config.bitcoin_keywords)?.matcher()?;
 
-        for feed_url in rss_feeds {
-            match self.process_rss_feed(feed_url, &keyword_regex).await {
+        for feed_url in &self.config.endpoints.rss_feeds {
+            match self.process_rss_feed(feed_url, &matcher).await {
                 Ok(count) => {
                     total_added += count;
                     tracing::info!("Собрано {} новостей из {}", count, feed_url);
@@ -507,7 +831,7 @@ impl DataCollectorService {
         }
     }
 
-    async fn process_rss_feed(&self, feed_url: &str, keyword_regex: &regex::Regex) -> Result<usize> {
+    async fn process_rss_feed(&self, feed_url: &str, matcher: &QueryMatcher) -> Result<usize> {
         let response = self.client.get(feed_url).send().await?;
         let content = response.bytes().await?;
         
@@ -516,6 +840,10 @@ impl DataCollectorService {
 
         let mut added_count = 0;
         let max_articles = self.config.max_articles.unwrap_or(20);
+        let allowed_languages = &self.config.languages.rss;
+        let feed_language = feed.language.as_deref()
+            .and_then(|l| l.split(['-', '_']).next())
+            .map(|l| l.to_lowercase());
 
         for entry in feed.entries.iter().take(max_articles) {
             let title = entry.title.as_ref()
@@ -526,13 +854,21 @@ impl DataCollectorService {
                 .map(|text| text.content.clone())
                 .or_else(|| {
                     entry.content.as_ref().and_then(|content| {
-                        content.body.as_ref().map(|body| body.clone())
+                        content.body.clone()
                     })
                 })
                 .unwrap_or_default();
 
-            // Проверяем наличие ключевых слов
-            if keyword_regex.is_match(&title) || keyword_regex.is_match(&content) {
+            let language = detect_language(&format!("{} {}", title, content))
+                .or_else(|| feed_language.clone());
+            if !allowed_languages.is_empty()
+                && !language.as_ref().is_some_and(|l| allowed_languages.contains(l))
+            {
+                continue;
+            }
+
+            // Проверяем соответствие поисковому запросу
+            if matcher.is_match(&format!("{} {}", title, content)) {
                 let url = entry.links.first().map(|link| link.href.clone());
                 let published_at = entry.published.map(|dt| dt.to_rfc3339());
 
@@ -542,6 +878,8 @@ impl DataCollectorService {
                     is_positive: None, // Будет определено позже через анализ настроений
                     url,
                     published_at,
+                    language,
+                    ..Default::default()
                 }).await?;
                 
                 added_count += 1;
@@ -551,4 +889,121 @@ impl DataCollectorService {
         Ok(added_count)
     }
 
+    async fn collect_from_reddit(&self) -> Result<usize> {
+        let reddit = &self.config.reddit;
+        let client = ClientBuilder::new()
+            .timeout(std::time::Duration::from_secs(reddit.timeout_secs))
+            .user_agent(reddit.user_agent.clone())
+            .build()?;
+        let matcher = self.config.query.for_source("reddit", &self.config.bitcoin_keywords)?.matcher()?;
+
+        let mut total_added = 0;
+        let mut last_error = None;
+        for subreddit in &reddit.subreddits {
+            match self.process_subreddit(&client, subreddit, &matcher).await {
+                Ok(count) => {
+                    total_added += count;
+                    tracing::info!("Собрано {} постов из r/{}", count, subreddit);
+                }
+                Err(e) => {
+                    tracing::warn!("Ошибка обработки r/{}: {}", subreddit, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        match last_error {
+            Some(e) if total_added == 0 => Err(e),
+            _ => Ok(total_added),
+        }
+    }
+
+    async fn process_subreddit(&self, client: &Client, subreddit: &str, matcher: &QueryMatcher) -> Result<usize> {
+        let reddit = &self.config.reddit;
+        let days = self.amount_days.lock().await.days;
+        let base_url = reddit.base_url.trim_end_matches('/');
+
+        let mut url = format!(
+            "{}/r/{}/{}.json?limit={}&raw_json=1",
+            base_url,
+            urlencoding::encode(subreddit),
+            reddit.listing,
+            reddit.limit
+        );
+        if reddit.listing == "top" {
+            let period = match days {
+                0..=1 => "day",
+                2..=7 => "week",
+                8..=31 => "month",
+                _ => "year",
+            };
+            url.push_str(&format!("&t={}", period));
+        }
+
+        let response = client.get(&url).send().await?;
+        let status = response.status();
+        if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(BitcoinAnalysisError::RateLimited(format!("Reddit r/{}", subreddit)));
+        }
+        if !status.is_success() {
+            return Err(BitcoinAnalysisError::ApiError(format!("Reddit r/{}: статус {}", subreddit, status)));
+        }
+
+        let json: Value = response.json().await?;
+        let posts = json["data"]["children"].as_array().ok_or_else(|| {
+            BitcoinAnalysisError::InvalidDataFormat("Отсутствует поле data.children".to_string())
+        })?;
+
+        // В тематических сабреддитах все посты по теме, в общих фильтруем поисковым запросом
+        let filter_by_query = !reddit.unfiltered_subreddits.iter().any(|s| s.eq_ignore_ascii_case(subreddit));
+        let since = Utc::now() - chrono::Duration::days(days as i64);
+
+        let mut added_count = 0;
+        for post in posts {
+            let data = &post["data"];
+            // Закреплённые посты модераторов — правила и ежедневные обсуждения
+            if data["stickied"].as_bool() == Some(true) {
+                continue;
+            }
+            let score = data["score"].as_i64().unwrap_or(0);
+            let comments = data["num_comments"].as_u64().unwrap_or(0);
+            if score < reddit.min_score || comments < reddit.min_comments {
+                continue;
+            }
+
+            let created = data["created_utc"].as_f64()
+                .and_then(|timestamp| DateTime::from_timestamp(timestamp as i64, 0));
+            if created.is_some_and(|created| created < since) {
+                continue;
+            }
+
+            let title = data["title"].as_str().unwrap_or("").to_string();
+            let content = data["selftext"].as_str().unwrap_or("").to_string();
+            if filter_by_query && !matcher.is_match(&format!("{} {}", title, content)) {
+                continue;
+            }
+
+            let language = detect_language(&format!("{} {}", title, content));
+            self.news_holder.add(BitcoinNews {
+                title,
+                content,
+                is_positive: None,
+                url: data["permalink"].as_str().map(|permalink| format!("{}{}", base_url, permalink)),
+                published_at: created.map(|created| created.to_rfc3339()),
+                language,
+                source_kind: SourceKind::Social,
+                ..Default::default()
+            }).await?;
+            added_count += 1;
+        }
+
+        Ok(added_count)
+    }
+}
+
+/// Первая дата, не входящая в период из `days` дней, заканчивающийся `end_date`
+fn period_start(end_date: NaiveDate, days: u32) -> Result<NaiveDate> {
+    end_date.checked_sub_days(Days::new(days as u64)).ok_or_else(|| {
+        BitcoinAnalysisError::InvalidDataFormat("Невозможно вычислить дату".to_string())
+    })
 }
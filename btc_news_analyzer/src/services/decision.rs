@@ -1,16 +1,27 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 use tokio::sync::Mutex;
 use chrono::Utc;
 
+use crate::analytics::{analyze_sentiment_price_correlation, build_sentiment_series, rank_news, summarize_aspects, summarize_entities};
+use crate::config::{AppConfig, DecisionConfig};
 use crate::holders::{BitcoinPriceHolder, BitcoinNewsHolder};
-use crate::models::{AmountDays, PriceStatistics, NewsStatistics, NewsItem, AnalysisResult};
+use crate::models::{
+    AmountDays, PriceStatistics, NewsStatistics, NewsItem, AnalysisResult,
+    BitcoinNews, BitcoinPrice, ConfidenceBreakdown, ConfidenceCriterion, DecisionExplanation, LanguageStatistics, SentimentBreakdown,
+    SourceKindStatistics,
+};
 use crate::errors::{BitcoinAnalysisError, Result};
+use crate::i18n::Locale;
+
+const TRADING_DAYS_PER_YEAR: f64 = 365.0;
 
 #[derive(Clone)]
 pub struct DataMakerDecisionService {
     price_holder: BitcoinPriceHolder,
     news_holder: BitcoinNewsHolder,
     amount_days: Arc<Mutex<AmountDays>>,
+    config: AppConfig,
 }
 
 impl DataMakerDecisionService {
@@ -18,15 +29,27 @@ impl DataMakerDecisionService {
         price_holder: BitcoinPriceHolder,
         news_holder: BitcoinNewsHolder,
         amount_days: Arc<Mutex<AmountDays>>,
+        config: AppConfig,
     ) -> Self {
         DataMakerDecisionService {
             price_holder,
             news_holder,
             amount_days,
+            config,
         }
     }
 
+    /// Веса и пороги из секции `[decision]` конфигурации
+    pub fn parameters(&self) -> &DecisionConfig {
+        &self.config.decision
+    }
+
     pub async fn make_decision(&self) -> Result<AnalysisResult> {
+        self.make_decision_with(&self.config.decision, Locale::default()).await
+    }
+
+    /// Принятие решения с явно заданными весами, порогами и языком резюме (например, из запроса)
+    pub async fn make_decision_with(&self, params: &DecisionConfig, locale: Locale) -> Result<AnalysisResult> {
         let days = {
             let amount_days = self.amount_days.lock().await;
             amount_days.days
@@ -38,19 +61,27 @@ impl DataMakerDecisionService {
         let end_price = self.price_holder.end_price().await?.ok_or(BitcoinAnalysisError::PriceDataUnavailable)?;
 
         // Рассчитываем статистику цен
-        let price_statistics = self.calculate_price_statistics(&prices, start_price, end_price)?;
+        let price_statistics = self.calculate_price_statistics(&prices, start_price, end_price, params)?;
 
         // Получаем и анализируем новости
         let news_items = self.news_holder.get().await?;
         let news_statistics = self.calculate_news_statistics(&news_items);
-        let key_news = self.format_key_news(&news_items);
+        let key_news = self.format_key_news(&news_items, params);
+        let aspect_sentiment = summarize_aspects(&news_items);
+        let top_entities = summarize_entities(&news_items, self.config.entities.top_n);
+        let granularity = self.config.sentiment_series_granularity.unwrap_or_default();
+        let sentiment_series = build_sentiment_series(&prices, &news_items, granularity);
+        let sentiment_price_correlation = analyze_sentiment_price_correlation(&prices, &news_items);
 
         // Определяем общий настрой рынка
-        let market_sentiment = self.determine_market_sentiment(&price_statistics, &news_statistics);
-        let confidence_level = self.determine_confidence_level(&price_statistics, &news_statistics);
+        let sentiment_breakdown = self.determine_market_sentiment(&price_statistics, &news_statistics, params);
+        let confidence_breakdown = self.determine_confidence_level(&price_statistics, &news_statistics, params);
+        let market_sentiment = sentiment_breakdown.label.clone();
+        let confidence_level = confidence_breakdown.level.clone();
 
         // Создаем краткое резюме
-        let summary = self.generate_summary(&price_statistics, &news_statistics, &market_sentiment);
+        let summary = self.generate_summary(&price_statistics, &news_statistics, &market_sentiment, locale);
+        let market_sentiment_description = sentiment_description(&market_sentiment, locale).to_string();
 
         Ok(AnalysisResult {
             analysis_period_days: days,
@@ -59,13 +90,34 @@ impl DataMakerDecisionService {
             price_statistics,
             news_statistics,
             key_news,
+            aspect_sentiment,
+            top_entities,
+            sentiment_series,
+            sentiment_price_correlation,
             market_sentiment,
+            market_sentiment_description,
             confidence_level,
             summary,
+            language: locale.code().to_string(),
+            decision_parameters: params.clone(),
+            decision_explanation: DecisionExplanation {
+                market_sentiment: sentiment_breakdown,
+                confidence: confidence_breakdown,
+            },
+            newsapi_usage: None,
         })
     }
 
-    fn calculate_price_statistics(&self, prices: &[crate::models::BitcoinPrice], start_price: f64, end_price: f64) -> Result<PriceStatistics> {
+    /// Только итоговый настрой рынка по окну цен и новостей, без аспектов, сущностей,
+    /// корреляции и временного ряда; цены — по возрастанию даты
+    pub fn market_sentiment(&self, prices: &[BitcoinPrice], news_items: &[BitcoinNews], params: &DecisionConfig) -> Result<String> {
+        let (first, last) = prices.first().zip(prices.last()).ok_or(BitcoinAnalysisError::PriceDataUnavailable)?;
+        let price_statistics = self.calculate_price_statistics(prices, first.price, last.price, params)?;
+        let news_statistics = self.calculate_news_statistics(news_items);
+        Ok(self.determine_market_sentiment(&price_statistics, &news_statistics, params).label)
+    }
+
+    fn calculate_price_statistics(&self, prices: &[crate::models::BitcoinPrice], start_price: f64, end_price: f64, params: &DecisionConfig) -> Result<PriceStatistics> {
         if prices.is_empty() {
             return Err(BitcoinAnalysisError::PriceDataUnavailable);
         }
@@ -78,16 +130,51 @@ impl DataMakerDecisionService {
         let price_change_absolute = end_price - start_price;
         let price_change_percentage = (price_change_absolute / start_price) * 100.0;
 
-        // Рассчитываем волатильность (стандартное отклонение)
-        let variance = price_values.iter()
-            .map(|&price| (price - average_price).powi(2))
-            .sum::<f64>() / price_values.len() as f64;
-        let volatility = variance.sqrt();
+        // Дневные лог-доходности: сопоставимы между окнами и не зависят от уровня цены
+        let log_returns: Vec<f64> = price_values
+            .windows(2)
+            .filter(|pair| pair[0] > 0.0 && pair[1] > 0.0)
+            .map(|pair| (pair[1] / pair[0]).ln())
+            .collect();
+
+        let daily_volatility = if log_returns.len() > 1 {
+            let mean_return = log_returns.iter().sum::<f64>() / log_returns.len() as f64;
+            let variance = log_returns.iter()
+                .map(|&r| (r - mean_return).powi(2))
+                .sum::<f64>() / (log_returns.len() - 1) as f64;
+            variance.sqrt() * 100.0
+        } else {
+            0.0
+        };
+        // Bitcoin торгуется круглосуточно, поэтому в году 365 торговых дней
+        let annualized_volatility = daily_volatility * TRADING_DAYS_PER_YEAR.sqrt();
+
+        let average_abs_daily_change = if log_returns.is_empty() {
+            0.0
+        } else {
+            log_returns.iter().map(|r| r.exp_m1().abs()).sum::<f64>() / log_returns.len() as f64 * 100.0
+        };
+
+        // Максимальная просадка от исторического пика внутри окна
+        let mut peak = f64::NEG_INFINITY;
+        let mut max_drawdown: f64 = 0.0;
+        for &price in &price_values {
+            peak = peak.max(price);
+            if peak > 0.0 {
+                max_drawdown = max_drawdown.max((peak - price) / peak * 100.0);
+            }
+        }
+
+        let price_range_percentage = if lowest_price > 0.0 {
+            (highest_price - lowest_price) / lowest_price * 100.0
+        } else {
+            0.0
+        };
 
         // Определяем тренд
-        let trend = if price_change_percentage > 2.0 {
+        let trend = if price_change_percentage > params.moderate_price_change_pct {
             "bullish".to_string()
-        } else if price_change_percentage < -2.0 {
+        } else if price_change_percentage < -params.moderate_price_change_pct {
             "bearish".to_string()
         } else {
             "sideways".to_string()
@@ -101,15 +188,50 @@ impl DataMakerDecisionService {
             highest_price,
             lowest_price,
             average_price,
-            volatility,
+            daily_volatility,
+            annualized_volatility,
+            max_drawdown,
+            price_range_percentage,
+            average_abs_daily_change,
+            data_points: price_values.len(),
             trend,
         })
     }
 
     fn calculate_news_statistics(&self, news_items: &[crate::models::BitcoinNews]) -> NewsStatistics {
-        let total_analyzed = news_items.len();
-        let positive_count = news_items.iter().filter(|n| n.is_positive == Some(true)).count();
-        let negative_count = news_items.iter().filter(|n| n.is_positive == Some(false)).count();
+        let duplicates_removed = news_items.iter().map(|n| n.duplicate_count).sum();
+
+        // Каждый сюжет учитывается один раз со средним настроением входящих в него статей
+        let mut stories: HashMap<usize, (f64, usize, f64)> = HashMap::new();
+        for (index, news) in news_items.iter().enumerate() {
+            let story = stories.entry(news.story_id.unwrap_or(usize::MAX - index)).or_insert((0.0, 0, 0.0));
+            story.0 += news.sentiment_value();
+            story.1 += 1;
+            story.2 += news.relevance.unwrap_or(1.0);
+        }
+        let story_count = stories.len();
+
+        // Направление (+1, 0, -1) и вес каждой учитываемой единицы; вес сюжета — средняя релевантность его статей
+        let story_level = self.config.dedup.story_level_statistics;
+        let units: Vec<(f64, f64)> = if story_level {
+            stories.values()
+                .map(|(sum, count, relevance)| (sign(sum / *count as f64), relevance / *count as f64))
+                .collect()
+        } else {
+            news_items.iter()
+                .map(|n| {
+                    let direction = match n.is_positive {
+                        Some(true) => 1.0,
+                        Some(false) => -1.0,
+                        None => 0.0,
+                    };
+                    (direction, n.relevance.unwrap_or(1.0))
+                })
+                .collect()
+        };
+        let total_analyzed = units.len();
+        let positive_count = units.iter().filter(|(direction, _)| *direction > 0.0).count();
+        let negative_count = units.iter().filter(|(direction, _)| *direction < 0.0).count();
         let neutral_count = total_analyzed - positive_count - negative_count;
 
         let positive_percentage = if total_analyzed > 0 {
@@ -124,13 +246,44 @@ impl DataMakerDecisionService {
             0.0
         };
 
-        // Рассчитываем общий sentiment score (-1.0 до 1.0)
-        let sentiment_score = if total_analyzed > 0 {
+        // Рассчитываем общий sentiment score (-1.0 до 1.0), при relevance.weight_sentiment — с весами релевантности
+        let sentiment_score = if self.config.relevance.weight_sentiment {
+            let total_weight: f64 = units.iter().map(|(_, weight)| weight).sum();
+            if total_weight > 0.0 {
+                units.iter().map(|(direction, weight)| direction * weight).sum::<f64>() / total_weight
+            } else {
+                0.0
+            }
+        } else if total_analyzed > 0 {
             (positive_count as f64 - negative_count as f64) / total_analyzed as f64
         } else {
             0.0
         };
 
+        // Разбивки по языкам и типам источников всегда считаются по статьям
+        let by_language = count_sentiment_by(news_items, |news| news.language.clone().unwrap_or_else(|| "unknown".to_string()))
+            .into_iter()
+            .map(|(language, counts)| LanguageStatistics {
+                language,
+                total: counts.total,
+                positive_count: counts.positive,
+                negative_count: counts.negative,
+                neutral_count: counts.neutral,
+                sentiment_score: counts.score(),
+            })
+            .collect();
+        let by_source_kind = count_sentiment_by(news_items, |news| news.source_kind.as_str().to_string())
+            .into_iter()
+            .map(|(source_kind, counts)| SourceKindStatistics {
+                source_kind,
+                total: counts.total,
+                positive_count: counts.positive,
+                negative_count: counts.negative,
+                neutral_count: counts.neutral,
+                sentiment_score: counts.score(),
+            })
+            .collect();
+
         NewsStatistics {
             total_analyzed,
             positive_count,
@@ -139,105 +292,371 @@ impl DataMakerDecisionService {
             positive_percentage,
             negative_percentage,
             sentiment_score,
+            statistics_unit: if story_level { "story" } else { "article" }.to_string(),
+            story_count,
+            duplicates_removed,
+            by_language,
+            by_source_kind,
         }
     }
 
-    fn format_key_news(&self, news_items: &[crate::models::BitcoinNews]) -> Vec<NewsItem> {
-        news_items.iter()
-            .take(5) // Берем только топ-5 новостей
-            .map(|news| {
+    fn format_key_news(&self, news_items: &[crate::models::BitcoinNews], params: &DecisionConfig) -> Vec<NewsItem> {
+        rank_news(news_items, &self.config.bitcoin_keywords, &self.config.ranking, &self.config.dedup)
+            .into_iter()
+            .take(params.key_news_count)
+            .map(|ranked| {
+                let news = &news_items[ranked.index];
                 let sentiment = match news.is_positive {
                     Some(true) => "positive",
                     Some(false) => "negative",
                     None => "neutral",
                 };
 
-                // Простая оценка уверенности на основе длины контента
-                let confidence = if news.content.len() > 100 {
-                    0.8
-                } else if news.content.len() > 50 {
-                    0.6
-                } else {
-                    0.4
-                };
-
                 NewsItem {
                     title: news.title.clone(),
                     sentiment: sentiment.to_string(),
-                    confidence,
+                    confidence: ranked.confidence,
                     published_at: news.published_at.clone(),
                     url: news.url.clone(),
+                    ranking_score: ranked.score,
+                    ranking_reasons: ranked.reasons,
+                    relevance: news.relevance,
                 }
             })
             .collect()
     }
 
-    fn determine_market_sentiment(&self, price_stats: &PriceStatistics, news_stats: &NewsStatistics) -> String {
-        let price_weight = 0.6;
-        let news_weight = 0.4;
-
-        let price_score = if price_stats.price_change_percentage > 5.0 {
-            1.0
-        } else if price_stats.price_change_percentage > 2.0 {
-            0.5
-        } else if price_stats.price_change_percentage < -5.0 {
-            -1.0
-        } else if price_stats.price_change_percentage < -2.0 {
-            -0.5
+    fn determine_market_sentiment(&self, price_stats: &PriceStatistics, news_stats: &NewsStatistics, params: &DecisionConfig) -> SentimentBreakdown {
+        let change = price_stats.price_change_percentage;
+        let (price_score, price_band) = if change > params.strong_price_change_pct {
+            (1.0, format!("price_change > {}%", params.strong_price_change_pct))
+        } else if change > params.moderate_price_change_pct {
+            (0.5, format!("price_change > {}%", params.moderate_price_change_pct))
+        } else if change < -params.strong_price_change_pct {
+            (-1.0, format!("price_change < -{}%", params.strong_price_change_pct))
+        } else if change < -params.moderate_price_change_pct {
+            (-0.5, format!("price_change < -{}%", params.moderate_price_change_pct))
         } else {
-            0.0
+            (0.0, format!("|price_change| <= {}%", params.moderate_price_change_pct))
         };
 
-        let combined_score = price_score * price_weight + news_stats.sentiment_score * news_weight;
+        let weighted_price_score = price_score * params.price_weight;
+        let weighted_news_score = news_stats.sentiment_score * params.news_weight;
+        let combined_score = weighted_price_score + weighted_news_score;
+
+        let strong = params.strong_sentiment_threshold;
+        let moderate = params.moderate_sentiment_threshold;
+        let (label, sentiment_band) = match combined_score {
+            x if x > strong => ("very_bullish", format!("combined_score > {}", strong)),
+            x if x > moderate => ("bullish", format!("combined_score > {}", moderate)),
+            x if x < -strong => ("very_bearish", format!("combined_score < -{}", strong)),
+            x if x < -moderate => ("bearish", format!("combined_score < -{}", moderate)),
+            _ => ("neutral", format!("|combined_score| <= {}", moderate)),
+        };
 
-        match combined_score {
-            x if x > 0.6 => "very_bullish",
-            x if x > 0.2 => "bullish",
-            x if x < -0.6 => "very_bearish",
-            x if x < -0.2 => "bearish",
-            _ => "neutral",
-        }.to_string()
+        SentimentBreakdown {
+            label: label.to_string(),
+            price_change_percentage: change,
+            price_score,
+            price_band,
+            news_sentiment_score: news_stats.sentiment_score,
+            price_weight: params.price_weight,
+            news_weight: params.news_weight,
+            weighted_price_score,
+            weighted_news_score,
+            combined_score,
+            sentiment_band,
+        }
     }
 
-    fn determine_confidence_level(&self, price_stats: &PriceStatistics, news_stats: &NewsStatistics) -> String {
-        let has_sufficient_news = news_stats.total_analyzed >= 3;
-        let price_change_significant = price_stats.price_change_percentage.abs() > 1.0;
-        let low_volatility = price_stats.volatility < price_stats.average_price * 0.05;
+    fn determine_confidence_level(&self, price_stats: &PriceStatistics, news_stats: &NewsStatistics, params: &DecisionConfig) -> ConfidenceBreakdown {
+        let sufficient_news = ConfidenceCriterion {
+            name: "sufficient_news".to_string(),
+            passed: news_stats.total_analyzed >= params.min_news_for_confidence,
+            observed: news_stats.total_analyzed as f64,
+            threshold: params.min_news_for_confidence as f64,
+            rule: "total_analyzed >= threshold".to_string(),
+        };
+
+        // Изменение значимо, если превышает ожидаемый шум за окно (σ дневной * √N)
+        let observed_days = (price_stats.data_points.saturating_sub(1) as f64).max(1.0);
+        let expected_noise = price_stats.daily_volatility * observed_days.sqrt();
+        let significance_threshold = expected_noise.max(params.min_significant_price_change_pct);
+        let price_change_significant = ConfidenceCriterion {
+            name: "price_change_significant".to_string(),
+            passed: price_stats.price_change_percentage.abs() > significance_threshold,
+            observed: price_stats.price_change_percentage.abs(),
+            threshold: significance_threshold,
+            rule: "|price_change_percentage| > max(daily_volatility * sqrt(days), min_significant_price_change_pct)".to_string(),
+        };
+
+        let low_daily_volatility = ConfidenceCriterion {
+            name: "low_daily_volatility".to_string(),
+            passed: price_stats.daily_volatility < params.low_daily_volatility_pct,
+            observed: price_stats.daily_volatility,
+            threshold: params.low_daily_volatility_pct,
+            rule: "daily_volatility < threshold".to_string(),
+        };
+
+        let low_max_drawdown = ConfidenceCriterion {
+            name: "low_max_drawdown".to_string(),
+            passed: price_stats.max_drawdown < params.low_max_drawdown_pct,
+            observed: price_stats.max_drawdown,
+            threshold: params.low_max_drawdown_pct,
+            rule: "max_drawdown < threshold".to_string(),
+        };
 
-        if has_sufficient_news && price_change_significant && low_volatility {
-            "high"
-        } else if has_sufficient_news || price_change_significant {
-            "medium"
+        let low_volatility = low_daily_volatility.passed && low_max_drawdown.passed;
+        let (level, rule_applied) = if sufficient_news.passed && price_change_significant.passed && low_volatility {
+            ("high", "all criteria passed")
+        } else if sufficient_news.passed || price_change_significant.passed {
+            ("medium", "sufficient_news or price_change_significant passed")
         } else {
-            "low"
-        }.to_string()
-    }
-
-    fn generate_summary(&self, price_stats: &PriceStatistics, news_stats: &NewsStatistics, market_sentiment: &str) -> String {
-        let price_direction = if price_stats.price_change_percentage > 0.0 { "выросла" } else { "упала" };
-        let sentiment_description = match market_sentiment {
-            "very_bullish" => "крайне позитивные",
-            "bullish" => "позитивные",
-            "neutral" => "нейтральные",
-            "bearish" => "негативные",
-            "very_bearish" => "крайне негативные",
-            _ => "смешанные",
-        };
-
-        format!(
-            "За анализируемый период цена Bitcoin {} на {:.2}% (с ${:.2} до ${:.2}). \
-            Проанализировано {} новостей, из которых {}% позитивных и {}% негативных. \
-            Общие настроения рынка: {}. Волатильность составила ${:.2}.",
-            price_direction,
-            price_stats.price_change_percentage.abs(),
-            price_stats.start_price,
-            price_stats.end_price,
-            news_stats.total_analyzed,
-            news_stats.positive_percentage.round(),
-            news_stats.negative_percentage.round(),
-            sentiment_description,
-            price_stats.volatility
-        )
+            ("low", "neither sufficient_news nor price_change_significant passed")
+        };
+
+        ConfidenceBreakdown {
+            level: level.to_string(),
+            rule_applied: rule_applied.to_string(),
+            criteria: vec![sufficient_news, price_change_significant, low_daily_volatility, low_max_drawdown],
+        }
+    }
+
+    fn generate_summary(&self, price_stats: &PriceStatistics, news_stats: &NewsStatistics, market_sentiment: &str, locale: Locale) -> String {
+        let price_direction = if price_stats.price_change_percentage > 0.0 {
+            locale.message("price_direction.up")
+        } else {
+            locale.message("price_direction.down")
+        };
+
+        locale.format("summary", &[
+            ("direction", price_direction.to_string()),
+            ("change", format!("{:.2}", price_stats.price_change_percentage.abs())),
+            ("start", format!("{:.2}", price_stats.start_price)),
+            ("end", format!("{:.2}", price_stats.end_price)),
+            ("total", news_stats.total_analyzed.to_string()),
+            ("positive", news_stats.positive_percentage.round().to_string()),
+            ("negative", news_stats.negative_percentage.round().to_string()),
+            ("sentiment", sentiment_description(market_sentiment, locale).to_string()),
+            ("daily_volatility", format!("{:.2}", price_stats.daily_volatility)),
+            ("annualized_volatility", format!("{:.2}", price_stats.annualized_volatility)),
+            ("max_drawdown", format!("{:.2}", price_stats.max_drawdown)),
+        ])
     }
 }
 
+#[derive(Default)]
+struct SentimentCounts {
+    total: usize,
+    positive: usize,
+    negative: usize,
+    neutral: usize,
+}
+
+impl SentimentCounts {
+    fn score(&self) -> f64 {
+        (self.positive as f64 - self.negative as f64) / self.total as f64
+    }
+}
+
+/// Число позитивных, негативных и нейтральных статей в каждой группе, по убыванию размера группы
+fn count_sentiment_by(news_items: &[BitcoinNews], key: impl Fn(&BitcoinNews) -> String) -> Vec<(String, SentimentCounts)> {
+    let mut groups: HashMap<String, SentimentCounts> = HashMap::new();
+    for news in news_items {
+        let counts = groups.entry(key(news)).or_default();
+        counts.total += 1;
+        match news.is_positive {
+            Some(true) => counts.positive += 1,
+            Some(false) => counts.negative += 1,
+            None => counts.neutral += 1,
+        }
+    }
+    let mut groups: Vec<(String, SentimentCounts)> = groups.into_iter().collect();
+    groups.sort_by(|a, b| b.1.total.cmp(&a.1.total).then_with(|| a.0.cmp(&b.0)));
+    groups
+}
+
+fn sign(value: f64) -> f64 {
+    if value > 0.0 {
+        1.0
+    } else if value < 0.0 {
+        -1.0
+    } else {
+        0.0
+    }
+}
+
+fn sentiment_description(market_sentiment: &str, locale: Locale) -> &'static str {
+    match market_sentiment {
+        "very_bullish" | "bullish" | "neutral" | "bearish" | "very_bearish" => {
+            locale.message(&format!("sentiment.{}", market_sentiment))
+        }
+        _ => locale.message("sentiment.mixed"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn service() -> DataMakerDecisionService {
+        let config: AppConfig = serde_json::from_value(serde_json::json!({
+            "newsapi_url": "http://localhost",
+            "newsapi_key": "",
+            "huggingface_api_url": "http://localhost",
+            "huggingface_api_key": "",
+            "bitcoin_keywords": ["bitcoin"],
+        }))
+        .unwrap();
+        DataMakerDecisionService::new(
+            BitcoinPriceHolder::new(),
+            BitcoinNewsHolder::new(),
+            Arc::new(Mutex::new(AmountDays { days: 7 })),
+            config,
+        )
+    }
+
+    // 8 точек: ожидаемый шум окна равен daily_volatility * sqrt(7)
+    fn price_stats(change: f64, daily_volatility: f64, max_drawdown: f64) -> PriceStatistics {
+        PriceStatistics {
+            start_price: 100.0,
+            end_price: 100.0 + change,
+            price_change_absolute: change,
+            price_change_percentage: change,
+            highest_price: 100.0 + change.max(0.0),
+            lowest_price: 100.0 + change.min(0.0),
+            average_price: 100.0,
+            daily_volatility,
+            annualized_volatility: daily_volatility * TRADING_DAYS_PER_YEAR.sqrt(),
+            max_drawdown,
+            price_range_percentage: change.abs(),
+            average_abs_daily_change: daily_volatility,
+            data_points: 8,
+            trend: "sideways".to_string(),
+        }
+    }
+
+    fn news_stats(total_analyzed: usize, sentiment_score: f64) -> NewsStatistics {
+        NewsStatistics {
+            total_analyzed,
+            positive_count: 0,
+            negative_count: 0,
+            neutral_count: total_analyzed,
+            positive_percentage: 0.0,
+            negative_percentage: 0.0,
+            sentiment_score,
+            statistics_unit: "article".to_string(),
+            story_count: total_analyzed,
+            duplicates_removed: 0,
+            by_language: Vec::new(),
+            by_source_kind: Vec::new(),
+        }
+    }
+
+    fn criterion<'a>(breakdown: &'a ConfidenceBreakdown, name: &str) -> &'a ConfidenceCriterion {
+        breakdown.criteria.iter().find(|c| c.name == name).unwrap()
+    }
+
+    fn failed(breakdown: &ConfidenceBreakdown) -> Vec<&str> {
+        breakdown.criteria.iter().filter(|c| !c.passed).map(|c| c.name.as_str()).collect()
+    }
+
+    #[test]
+    fn all_confidence_criteria_pass() {
+        let params = DecisionConfig::default();
+        let confidence = service().determine_confidence_level(&price_stats(8.0, 1.0, 2.0), &news_stats(5, 0.5), &params);
+
+        assert_eq!(confidence.level, "high");
+        assert_eq!(confidence.rule_applied, "all criteria passed");
+        let names: Vec<&str> = confidence.criteria.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(names, ["sufficient_news", "price_change_significant", "low_daily_volatility", "low_max_drawdown"]);
+        assert!(failed(&confidence).is_empty());
+
+        let news = criterion(&confidence, "sufficient_news");
+        assert_eq!((news.observed, news.threshold), (5.0, 3.0));
+        let significance = criterion(&confidence, "price_change_significant");
+        assert_eq!(significance.observed, 8.0);
+        assert!((significance.threshold - 7f64.sqrt()).abs() < 1e-12);
+    }
+
+    #[test]
+    fn insufficient_news_lowers_confidence_to_medium() {
+        let confidence = service().determine_confidence_level(&price_stats(8.0, 1.0, 2.0), &news_stats(2, 0.5), &DecisionConfig::default());
+        assert_eq!(failed(&confidence), ["sufficient_news"]);
+        assert_eq!(confidence.level, "medium");
+        assert_eq!(confidence.rule_applied, "sufficient_news or price_change_significant passed");
+    }
+
+    #[test]
+    fn price_change_within_window_noise_is_not_significant() {
+        let confidence = service().determine_confidence_level(&price_stats(2.0, 1.0, 2.0), &news_stats(5, 0.5), &DecisionConfig::default());
+        assert_eq!(failed(&confidence), ["price_change_significant"]);
+        assert_eq!(confidence.level, "medium");
+
+        // Без волатильности порог значимости не опускается ниже min_significant_price_change_pct
+        let flat = service().determine_confidence_level(&price_stats(0.5, 0.0, 0.0), &news_stats(5, 0.5), &DecisionConfig::default());
+        let significance = criterion(&flat, "price_change_significant");
+        assert!(!significance.passed);
+        assert_eq!(significance.threshold, 1.0);
+    }
+
+    #[test]
+    fn high_daily_volatility_lowers_confidence_to_medium() {
+        // Шум окна 4 * sqrt(7) ≈ 10.6%, изменение 15% остается значимым
+        let confidence = service().determine_confidence_level(&price_stats(15.0, 4.0, 2.0), &news_stats(5, 0.5), &DecisionConfig::default());
+        assert_eq!(failed(&confidence), ["low_daily_volatility"]);
+        assert_eq!(criterion(&confidence, "low_daily_volatility").observed, 4.0);
+        assert_eq!(confidence.level, "medium");
+    }
+
+    #[test]
+    fn deep_drawdown_lowers_confidence_to_medium() {
+        let confidence = service().determine_confidence_level(&price_stats(8.0, 1.0, 12.0), &news_stats(5, 0.5), &DecisionConfig::default());
+        assert_eq!(failed(&confidence), ["low_max_drawdown"]);
+        assert_eq!(criterion(&confidence, "low_max_drawdown").threshold, 10.0);
+        assert_eq!(confidence.level, "medium");
+    }
+
+    #[test]
+    fn no_news_and_insignificant_change_give_low_confidence() {
+        let confidence = service().determine_confidence_level(&price_stats(0.5, 1.0, 2.0), &news_stats(0, 0.0), &DecisionConfig::default());
+        assert_eq!(failed(&confidence), ["sufficient_news", "price_change_significant"]);
+        assert_eq!(confidence.level, "low");
+        assert_eq!(confidence.rule_applied, "neither sufficient_news nor price_change_significant passed");
+    }
+
+    #[test]
+    fn sentiment_breakdown_reports_bands_and_weighted_scores() {
+        let service = service();
+        let params = DecisionConfig::default();
+
+        let very_bullish = service.determine_market_sentiment(&price_stats(8.0, 1.0, 2.0), &news_stats(5, 0.5), &params);
+        assert_eq!(very_bullish.label, "very_bullish");
+        assert_eq!(very_bullish.price_score, 1.0);
+        assert_eq!(very_bullish.price_band, "price_change > 5%");
+        assert!((very_bullish.weighted_price_score - 0.6).abs() < 1e-12);
+        assert!((very_bullish.weighted_news_score - 0.2).abs() < 1e-12);
+        assert!((very_bullish.combined_score - 0.8).abs() < 1e-12);
+        assert_eq!(very_bullish.sentiment_band, "combined_score > 0.6");
+
+        let bearish = service.determine_market_sentiment(&price_stats(-3.0, 1.0, 3.0), &news_stats(5, -0.2), &params);
+        assert_eq!(bearish.label, "bearish");
+        assert_eq!(bearish.price_score, -0.5);
+        assert_eq!(bearish.price_band, "price_change < -2%");
+        assert!((bearish.combined_score + 0.38).abs() < 1e-12);
+        assert_eq!(bearish.sentiment_band, "combined_score < -0.2");
+
+        let neutral = service.determine_market_sentiment(&price_stats(1.0, 1.0, 0.0), &news_stats(0, 0.0), &params);
+        assert_eq!(neutral.label, "neutral");
+        assert_eq!(neutral.price_band, "|price_change| <= 2%");
+        assert_eq!(neutral.sentiment_band, "|combined_score| <= 0.2");
+    }
+
+    #[test]
+    fn sentiment_weights_come_from_parameters() {
+        let params = DecisionConfig { price_weight: 0.0, news_weight: 1.0, ..DecisionConfig::default() };
+        let breakdown = service().determine_market_sentiment(&price_stats(8.0, 1.0, 2.0), &news_stats(5, -0.7), &params);
+        assert_eq!((breakdown.price_weight, breakdown.news_weight), (0.0, 1.0));
+        assert_eq!(breakdown.label, "very_bearish");
+    }
+}
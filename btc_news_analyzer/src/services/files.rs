@@ -0,0 +1,370 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::analytics::detect_language;
+use crate::config::FileSourcesConfig;
+use crate::errors::{BitcoinAnalysisError, Result};
+use crate::models::{BitcoinNews, BitcoinPrice, SourceKind};
+use crate::services::sources::parse_published;
+
+// Поддерживаемые расширения файлов новостей
+const NEWS_EXTENSIONS: &[&str] = &["jsonl", "ndjson", "json", "csv", "rss", "atom", "xml"];
+
+// Имена полей JSON и столбцов CSV, без учёта регистра
+const TITLE_FIELDS: &[&str] = &["title", "headline"];
+const CONTENT_FIELDS: &[&str] = &["content", "body", "text", "description", "summary"];
+const URL_FIELDS: &[&str] = &["url", "link"];
+const DATE_FIELDS: &[&str] = &["published_at", "publishedat", "published", "date", "datetime", "time", "timestamp", "seendate"];
+const SOURCE_FIELDS: &[&str] = &["source", "domain", "publisher"];
+const LANGUAGE_FIELDS: &[&str] = &["language", "lang"];
+const SOURCE_KIND_FIELDS: &[&str] = &["source_kind"];
+const TONE_FIELDS: &[&str] = &["source_tone", "tone"];
+
+// Столбцы CSV OHLC
+const PRICE_DATE_COLUMNS: &[&str] = &["date", "timestamp", "time", "datetime", "open_time", "open time"];
+const PRICE_CLOSE_COLUMNS: &[&str] = &["close", "adj close", "adj_close", "price"];
+
+/// Архив новостей в каталоге `[files] news_directory`: JSONL, JSON-массивы, CSV, RSS и Atom
+#[derive(Clone)]
+pub struct FileNewsSource {
+    config: FileSourcesConfig,
+}
+
+impl FileNewsSource {
+    pub fn new(config: FileSourcesConfig) -> Self {
+        FileNewsSource { config }
+    }
+
+    /// Новости всех поддерживаемых файлов каталога по порядку имён.
+    /// Файл с ошибкой пропускается с предупреждением
+    pub fn read(&self) -> Result<Vec<BitcoinNews>> {
+        let directory = self.config.news_directory.as_deref().ok_or_else(|| {
+            BitcoinAnalysisError::InvalidDataFormat("не задан files.news_directory".to_string())
+        })?;
+        let mut files = Vec::new();
+        collect_files(Path::new(directory), self.config.recursive, &mut files)?;
+        files.sort();
+
+        let mut news_items = Vec::new();
+        for path in files {
+            match self.read_file(&path) {
+                Ok(items) => {
+                    tracing::debug!("Прочитано {} новостей из {}", items.len(), path.display());
+                    news_items.extend(items);
+                }
+                Err(e) => tracing::warn!("Пропущен файл {}: {}", path.display(), e),
+            }
+        }
+        Ok(news_items)
+    }
+
+    fn read_file(&self, path: &Path) -> Result<Vec<BitcoinNews>> {
+        let bytes = read_limited(path, self.config.max_file_bytes)?;
+        match extension(path).as_str() {
+            "jsonl" | "ndjson" => Ok(String::from_utf8_lossy(&bytes)
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| !line.trim().is_empty())
+                .filter_map(|(line_number, line)| match serde_json::from_str::<Value>(line) {
+                    Ok(value) => value.as_object().and_then(news_from_record),
+                    Err(e) => {
+                        // Одна битая строка архива не должна отбрасывать весь файл
+                        tracing::warn!("Пропущена строка {} файла {}: {}", line_number + 1, path.display(), e);
+                        None
+                    }
+                })
+                .collect()),
+            "json" => {
+                let value: Value = serde_json::from_slice(&bytes)?;
+                // Массив статей или выгрузка NewsAPI с полем articles
+                let items = value.as_array().or_else(|| value["articles"].as_array()).ok_or_else(|| {
+                    BitcoinAnalysisError::InvalidDataFormat("ожидается массив статей".to_string())
+                })?;
+                Ok(items.iter().filter_map(Value::as_object).filter_map(news_from_record).collect())
+            }
+            "csv" => {
+                let mut reader = csv::Reader::from_reader(bytes.as_slice());
+                let headers: Vec<String> = reader.headers()?.iter().map(|h| h.trim().to_string()).collect();
+                let mut news_items = Vec::new();
+                for row in reader.records() {
+                    let row = row?;
+                    let record: Map<String, Value> = headers.iter()
+                        .zip(row.iter())
+                        .map(|(header, value)| (header.clone(), Value::String(value.to_string())))
+                        .collect();
+                    news_items.extend(news_from_record(&record));
+                }
+                Ok(news_items)
+            }
+            _ => {
+                let feed = feed_rs::parser::parse(bytes.as_slice())
+                    .map_err(|e| BitcoinAnalysisError::InvalidDataFormat(format!("RSS parse error: {}", e)))?;
+                Ok(feed.entries.into_iter()
+                    .filter_map(|entry| {
+                        let title = entry.title.map(|t| t.content)?;
+                        let content = entry.summary.map(|s| s.content)
+                            .or_else(|| entry.content.and_then(|c| c.body))
+                            .unwrap_or_default();
+                        let language = detect_language(&format!("{} {}", title, content));
+                        Some(BitcoinNews {
+                            title,
+                            content,
+                            is_positive: None,
+                            url: entry.links.first().map(|link| link.href.clone()),
+                            published_at: entry.published.or(entry.updated).map(|dt| dt.to_rfc3339()),
+                            language,
+                            ..Default::default()
+                        })
+                    })
+                    .collect())
+            }
+        }
+    }
+}
+
+/// Дневные цены закрытия из CSV OHLC (`[files] prices_csv`). Внутридневные строки
+/// сворачиваются в последнюю цену дня
+#[derive(Clone)]
+pub struct CsvPriceSource {
+    path: PathBuf,
+    max_file_bytes: u64,
+}
+
+impl CsvPriceSource {
+    pub fn new(path: impl Into<PathBuf>, max_file_bytes: u64) -> Self {
+        CsvPriceSource { path: path.into(), max_file_bytes }
+    }
+
+    /// Цены по возрастанию даты
+    pub fn read(&self) -> Result<Vec<BitcoinPrice>> {
+        let bytes = read_limited(&self.path, self.max_file_bytes)?;
+        let mut reader = csv::Reader::from_reader(bytes.as_slice());
+        let headers: Vec<String> = reader.headers()?.iter().map(|h| h.trim().to_lowercase()).collect();
+        let column = |names: &[&str]| names.iter().find_map(|name| headers.iter().position(|h| h == name));
+        let (Some(date_column), Some(close_column)) = (column(PRICE_DATE_COLUMNS), column(PRICE_CLOSE_COLUMNS)) else {
+            return Err(BitcoinAnalysisError::InvalidDataFormat(format!(
+                "{}: нужны столбцы даты ({}) и закрытия ({})",
+                self.path.display(),
+                PRICE_DATE_COLUMNS.join(", "),
+                PRICE_CLOSE_COLUMNS.join(", ")
+            )));
+        };
+
+        let mut closes: BTreeMap<NaiveDate, (DateTime<Utc>, f64)> = BTreeMap::new();
+        for (line, row) in reader.records().enumerate() {
+            let row = row?;
+            let timestamp = row.get(date_column)
+                .and_then(|value| parse_published(&Value::String(value.to_string())))
+                .and_then(|value| DateTime::parse_from_rfc3339(&value).ok())
+                .map(|datetime| datetime.with_timezone(&Utc));
+            let close = row.get(close_column).and_then(|value| value.trim().parse::<f64>().ok());
+            let (Some(timestamp), Some(close)) = (timestamp, close) else {
+                tracing::debug!("Пропущена строка {} файла цен {}", line + 2, self.path.display());
+                continue;
+            };
+            if !close.is_finite() || close <= 0.0 {
+                continue;
+            }
+            let entry = closes.entry(timestamp.date_naive()).or_insert((timestamp, close));
+            if timestamp >= entry.0 {
+                *entry = (timestamp, close);
+            }
+        }
+
+        Ok(closes.into_iter().map(|(date, (_, price))| BitcoinPrice { date, price }).collect())
+    }
+}
+
+fn collect_files(directory: &Path, recursive: bool, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            if recursive {
+                collect_files(&path, recursive, files)?;
+            }
+        } else if NEWS_EXTENSIONS.contains(&extension(&path).as_str()) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn extension(path: &Path) -> String {
+    path.extension().and_then(|e| e.to_str()).unwrap_or_default().to_lowercase()
+}
+
+fn read_limited(path: &Path, max_bytes: u64) -> Result<Vec<u8>> {
+    let size = fs::metadata(path)?.len();
+    if size > max_bytes {
+        return Err(BitcoinAnalysisError::InvalidDataFormat(format!(
+            "файл {} больше files.max_file_bytes ({} байт)",
+            path.display(),
+            max_bytes
+        )));
+    }
+    Ok(fs::read(path)?)
+}
+
+/// Новость из записи JSON или строки CSV; записи без заголовка пропускаются
+fn news_from_record(record: &Map<String, Value>) -> Option<BitcoinNews> {
+    // Первое имя из списка, которое есть в записи: порядок списка задаёт приоритет
+    let field = |names: &[&str]| {
+        names.iter().find_map(|name| {
+            record.iter()
+                .find(|(key, _)| key.to_lowercase() == *name)
+                .map(|(_, value)| value)
+        })
+    };
+    // Источник в выгрузках NewsAPI — объект {"id", "name"}
+    let text = |names: &[&str]| {
+        field(names)
+            .and_then(|value| value.as_str().or_else(|| value["name"].as_str()))
+            .map(|text| text.trim().to_string())
+            .filter(|text| !text.is_empty())
+    };
+
+    let title = text(TITLE_FIELDS)?;
+    let content = text(CONTENT_FIELDS).unwrap_or_default();
+    let language = text(LANGUAGE_FIELDS).or_else(|| detect_language(&format!("{} {}", title, content)));
+    Some(BitcoinNews {
+        title,
+        content,
+        is_positive: None,
+        url: text(URL_FIELDS),
+        published_at: field(DATE_FIELDS).and_then(parse_published),
+        language,
+        source: text(SOURCE_FIELDS),
+        source_kind: match text(SOURCE_KIND_FIELDS).as_deref() {
+            Some("social") => SourceKind::Social,
+            _ => SourceKind::Editorial,
+        },
+        source_tone: field(TONE_FIELDS).and_then(|value| value.as_f64().or_else(|| value.as_str()?.trim().parse().ok())),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Отдельный каталог на тест: тесты выполняются параллельно
+    fn directory(name: &str, files: &[(&str, &str)]) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("btc_files_unit_{}_{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        for (file, content) in files {
+            fs::write(directory.join(file), content).unwrap();
+        }
+        directory
+    }
+
+    fn source(directory: &Path) -> FileNewsSource {
+        FileNewsSource::new(FileSourcesConfig {
+            news_directory: Some(directory.to_string_lossy().into_owned()),
+            ..Default::default()
+        })
+    }
+
+    fn record(value: Value) -> Option<BitcoinNews> {
+        news_from_record(value.as_object().unwrap())
+    }
+
+    #[test]
+    fn malformed_jsonl_lines_are_skipped_individually() {
+        let directory = directory("jsonl", &[("news.jsonl", concat!(
+            r#"{"title": "Bitcoin ETF inflows", "lang": "en"}"#, "\n",
+            "{not json\n",
+            "\n",
+            r#"["not", "an", "object"]"#, "\n",
+            r#"{"content": "no title"}"#, "\n",
+            r#"{"title": "Bitcoin miners", "lang": "en"}"#, "\n",
+        ))]);
+
+        let titles: Vec<String> = source(&directory).read().unwrap().into_iter().map(|n| n.title).collect();
+        assert_eq!(titles, ["Bitcoin ETF inflows", "Bitcoin miners"]);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn broken_and_oversized_files_are_skipped() {
+        let directory = directory("broken", &[
+            ("a.json", "{not json"),
+            ("b.json", r#"{"articles": [{"title": "Bitcoin dump", "lang": "en"}]}"#),
+            ("c.txt", r#"{"title": "ignored"}"#),
+        ]);
+        let titles: Vec<String> = source(&directory).read().unwrap().into_iter().map(|n| n.title).collect();
+        assert_eq!(titles, ["Bitcoin dump"]);
+
+        let tiny = FileNewsSource::new(FileSourcesConfig {
+            news_directory: Some(directory.to_string_lossy().into_owned()),
+            max_file_bytes: 8,
+            ..Default::default()
+        });
+        assert!(tiny.read().unwrap().is_empty());
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn empty_directory_and_missing_setting() {
+        let directory = directory("empty", &[]);
+        assert!(source(&directory).read().unwrap().is_empty());
+        fs::remove_dir_all(&directory).unwrap();
+        assert!(FileNewsSource::new(FileSourcesConfig::default()).read().is_err());
+    }
+
+    #[test]
+    fn record_fields_follow_name_priority() {
+        let news = record(serde_json::json!({
+            "Headline": "Bitcoin miners sell",
+            "body": "short",
+            "Content": "Miners sell reserves",
+            "date": "2025-08-01",
+            "published_at": "2025-08-02T09:00:00Z",
+            "source": {"id": null, "name": "CoinDesk"},
+            "lang": "en",
+            "source_kind": "social",
+            "tone": "-2.5",
+        }))
+        .unwrap();
+        assert_eq!(news.title, "Bitcoin miners sell");
+        assert_eq!(news.content, "Miners sell reserves");
+        assert_eq!(news.published_at.as_deref(), Some("2025-08-02T09:00:00+00:00"));
+        assert_eq!(news.source.as_deref(), Some("CoinDesk"));
+        assert_eq!(news.source_kind, SourceKind::Social);
+        assert_eq!(news.source_tone, Some(-2.5));
+    }
+
+    #[test]
+    fn records_without_title_or_with_implausible_dates() {
+        assert!(record(serde_json::json!({"title": "  ", "content": "text"})).is_none());
+        let news = record(serde_json::json!({"title": "Bitcoin", "date": "2024", "lang": "en"})).unwrap();
+        assert_eq!(news.published_at, None);
+        assert_eq!(news.source_kind, SourceKind::Editorial);
+    }
+
+    #[test]
+    fn price_csv_keeps_last_close_per_day_and_skips_bad_rows() {
+        let directory = directory("prices", &[("prices.csv", "Date,Close\n\
+            20250817,60500\n\
+            2025-08-18 00:00:00,61000\n\
+            2025-08-18 12:00:00,61800\n\
+            bad row,oops\n\
+            2025-08-19,0\n\
+            2025,62000\n\
+            1755648000,63500\n")]);
+        let prices = CsvPriceSource::new(directory.join("prices.csv"), 1024).read().unwrap();
+        let closes: Vec<(String, f64)> = prices.iter().map(|p| (p.date.to_string(), p.price)).collect();
+        assert_eq!(closes, [
+            ("2025-08-17".to_string(), 60500.0),
+            ("2025-08-18".to_string(), 61800.0),
+            ("2025-08-20".to_string(), 63500.0),
+        ]);
+
+        fs::write(directory.join("no_close.csv"), "Date,Open\n2025-08-17,1\n").unwrap();
+        assert!(CsvPriceSource::new(directory.join("no_close.csv"), 1024).read().is_err());
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
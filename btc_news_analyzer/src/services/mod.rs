@@ -1,7 +1,15 @@
+pub mod article;
 pub mod collector;
 pub mod processor;
 pub mod decision;
+pub mod backtest;
+pub mod sources;
+pub mod files;
 
+pub use article::ArticleFetcher;
 pub use collector::DataCollectorService;
 pub use processor::DataProcessorService;
-pub use decision::DataMakerDecisionService;
\ No newline at end of file
+pub use decision::DataMakerDecisionService;
+pub use backtest::BacktestService;
+pub use sources::{GdeltSource, JsonNewsSource};
+pub use files::{CsvPriceSource, FileNewsSource};
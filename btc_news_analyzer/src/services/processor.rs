@@ -1,12 +1,22 @@
 use reqwest::{Client, ClientBuilder};
 use serde_json::{json, Value};
-use regex::Regex;
 use futures::stream::{self, StreamExt};
+use reqwest::StatusCode;
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::Arc;
 use std::time::Duration;
+use crate::analytics::{assign_stories, clean_text, deduplicate, detect_language, AspectClassifier, EntityExtractor, RelevanceScorer};
 use crate::holders::{BitcoinPriceHolder, BitcoinNewsHolder};
-use crate::config::AppConfig;
-use crate::errors::{BitcoinAnalysisError, Result};
-use crate::models::BitcoinNews;
+use crate::config::{AppConfig, SentimentEngine};
+use crate::errors::Result;
+use crate::models::{AspectSentiment, BitcoinNews};
+use crate::sentiment::{split_into_windows, LexiconSet, SentimentPrediction, TextWindow};
+#[cfg(feature = "onnx")]
+use crate::sentiment::OnnxSentimentModel;
+
+// Пауза перед повтором, если API не сообщил estimated_time
+const DEFAULT_RETRY_DELAY_SECS: f64 = 5.0;
 
 #[derive(Clone)]
 pub struct DataProcessorService {
@@ -14,19 +24,46 @@ pub struct DataProcessorService {
     price_holder: BitcoinPriceHolder,
     news_holder: BitcoinNewsHolder,
     config: AppConfig,
+    lexicons: Arc<LexiconSet>,
+    aspects: Arc<AspectClassifier>,
+    entities: Arc<EntityExtractor>,
+    relevance: Arc<RelevanceScorer>,
+    #[cfg(feature = "onnx")]
+    onnx_model: Option<Arc<OnnxSentimentModel>>,
 }
 
 impl DataProcessorService {
     pub fn new(price_holder: BitcoinPriceHolder, news_holder: BitcoinNewsHolder, config: AppConfig) -> Self {
         let client = ClientBuilder::new()
-            .timeout(Duration::from_secs(10))
+            .timeout(Duration::from_secs(config.sentiment.huggingface.timeout_secs))
             .build()
             .expect("Failed to build reqwest client");
+        let lexicons = Arc::new(LexiconSet::load(config.sentiment.lexicon_dir.as_deref().map(Path::new)));
+        let aspects = Arc::new(AspectClassifier::new(&config.aspects.taxonomy));
+        let entities = Arc::new(EntityExtractor::new(&config.entities));
+        // Запрос проверен при загрузке конфигурации; при ошибке оцениваем по bitcoin_keywords
+        let terms = config.query.query(&config.bitcoin_keywords)
+            .map(|query| query.terms())
+            .unwrap_or_else(|_| config.bitcoin_keywords.clone());
+        let relevance = Arc::new(RelevanceScorer::new(&terms, &config.relevance));
+        #[cfg(feature = "onnx")]
+        let onnx_model = (config.sentiment.engine == SentimentEngine::Onnx)
+            .then(|| OnnxSentimentModel::load(&config.sentiment.onnx, &config.sentiment.chunking))
+            .and_then(|loaded| loaded
+                .map_err(|e| tracing::error!("ONNX-модель не загружена, используется словарный анализ: {}", e))
+                .ok())
+            .map(Arc::new);
         DataProcessorService {
             client,
             price_holder,
             news_holder,
             config,
+            lexicons,
+            aspects,
+            entities,
+            relevance,
+            #[cfg(feature = "onnx")]
+            onnx_model,
         }
     }
 
@@ -35,28 +72,135 @@ impl DataProcessorService {
             .price_holder
             .end_price().await?
             .zip(self.price_holder.start_price().await?)
-            .map_or(false, |(end, start)| end > start);
+            .is_some_and(|(end, start)| end > start);
+
+        let mut news_items = self.news_holder.get().await?;
+        if self.config.dedup.enabled {
+            let (unique, removed) = deduplicate(news_items, &self.config.dedup);
+            tracing::info!("Удалено {} дубликатов новостей, осталось {}", removed, unique.len());
+            news_items = unique;
+        }
+
+        let mut prepared = Vec::new();
+        for mut news in news_items {
+            let cleaned_content = clean_text(&news.content);
+            let cleaned_title = clean_text(&news.title);
+
+            if cleaned_content.is_empty() && cleaned_title.is_empty() {
+                tracing::debug!("Пропущена новость из-за пустого контента или заголовка: {}", news.title);
+                continue;
+            }
+
+            // Полный текст статьи, если он загружен, точнее обрезанного фрагмента
+            let body = match news.full_text.as_deref() {
+                Some(full_text) => clean_text(full_text),
+                None => cleaned_content.clone(),
+            };
+            let text_to_analyze = format!("{} {}", cleaned_title, body);
+            news.language = news.language.clone().or_else(|| detect_language(&text_to_analyze));
+            news.content = cleaned_content;
+
+            if self.config.entities.enabled {
+                news.entities = self.entities.extract(&text_to_analyze);
+            }
+            if self.config.relevance.enabled {
+                let relevance = self.relevance.score(&cleaned_title, &text_to_analyze, &news.entities);
+                // Нерелевантные новости отбрасываются до обращения к модели настроений
+                if relevance < self.config.relevance.min_relevance {
+                    tracing::debug!("Пропущена нерелевантная новость ({:.2}): {}", relevance, news.title);
+                    continue;
+                }
+                news.relevance = Some(relevance);
+            }
+            prepared.push((news, text_to_analyze));
+        }
+
+        let inputs: Vec<(&str, Option<&str>)> = prepared.iter()
+            .map(|(news, text)| (text.as_str(), news.language.as_deref()))
+            .collect();
+        let predictions = self.analyze_sentiments(&inputs).await?;
+        let aspects = if self.config.aspects.enabled {
+            self.tag_aspects(&prepared, &predictions).await?
+        } else {
+            vec![Vec::new(); prepared.len()]
+        };
+
+        let mut processed = Vec::new();
+        for (((mut news, _), prediction), aspects) in prepared.into_iter().zip(predictions).zip(aspects) {
+            news.aspects = aspects;
+            news.is_positive = prediction.is_positive;
+            news.sentiment_score = Some(prediction.score);
+            news.sentiment_confidence = prediction.confidence;
+            tracing::debug!("Обработана новость: {}", news.title);
+            // Нейтральные новости не противоречат движению цены и не отбрасываются
+            if prediction.is_positive.is_none_or(|is_positive| is_positive == price_increased) {
+                processed.push(news);
+            }
+        }
+
+        if self.config.dedup.enabled {
+            let stories = assign_stories(&mut processed, &self.config.dedup);
+            tracing::info!("Новости сгруппированы в {} сюжетов", stories);
+        }
+
+        self.news_holder.clear().await?;
+        for news in processed {
+            self.news_holder.add(news).await?;
+        }
+
+        let len = self.news_holder.len().await?;
+        tracing::info!("Обработано {} новостей", len);
+        Ok(())
+    }
 
-        let news_items = self.news_holder.get().await?;
+    /// Оценка настроения набора текстов выбранным движком; модель и словарь выбираются по языку
+    async fn analyze_sentiments(&self, inputs: &[(&str, Option<&str>)]) -> Result<Vec<SentimentPrediction>> {
         let max_concurrent = self.config.max_concurrent_requests.unwrap_or(10);
-        let tasks: Vec<_> = news_items.iter().map(|news| {
-            let this = self.clone();
+
+        if self.config.sentiment.engine == SentimentEngine::Onnx {
+            let tasks: Vec<_> = inputs.iter()
+                .map(|&(text, language)| self.onnx_sentiment(text, language))
+                .collect();
+            let predictions = stream::iter(tasks)
+                .buffered(max_concurrent)
+                .collect()
+                .await;
+            return Ok(predictions);
+        }
+
+        if !self.config.sentiment.uses_huggingface(&self.config.huggingface_api_key) {
+            return Ok(inputs.iter().map(|&(text, language)| self.lexicon_sentiment(text, language)).collect());
+        }
+
+        // Длинные статьи режем на окна по лимиту токенов модели
+        let windows: Vec<(usize, TextWindow)> = inputs.iter()
+            .enumerate()
+            .flat_map(|(index, &(text, _))| {
+                split_into_windows(text, &self.config.sentiment.chunking)
+                    .into_iter()
+                    .map(move |window| (index, window))
+            })
+            .collect();
+
+        // Группируем окна по модели (она зависит от языка) и режем на пакеты
+        let mut by_model: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (position, (index, _)) in windows.iter().enumerate() {
+            by_model.entry(self.model_url(inputs[*index].1)).or_default().push(position);
+        }
+        let batch_size = self.config.sentiment.huggingface.batch_size;
+        let batches: Vec<(&str, Vec<usize>)> = by_model.iter()
+            .flat_map(|(&model_url, positions)| positions.chunks(batch_size).map(move |chunk| (model_url, chunk.to_vec())))
+            .collect();
+        tracing::info!(
+            "Анализ настроений {} новостей ({} фрагментов) в {} запросах к Hugging Face",
+            inputs.len(), windows.len(), batches.len()
+        );
+
+        let tasks: Vec<_> = batches.into_iter().map(|(model_url, positions)| {
+            let texts: Vec<String> = positions.iter().map(|&p| windows[p].1.text.clone()).collect();
             async move {
-                let cleaned_content = this.clean_text(&news.content)?;
-                let cleaned_title = this.clean_text(&news.title)?;
-
-                if !cleaned_content.is_empty() || !cleaned_title.is_empty() {
-                    let text_to_analyze = format!("{} {}", cleaned_title, cleaned_content);
-                    let is_positive = this.analyze_sentiment(&text_to_analyze).await?;
-                    let mut processed_news = news.clone();
-                    processed_news.content = cleaned_content;
-                    processed_news.is_positive = Some(is_positive);
-                    tracing::debug!("Обработана новость: {}", news.title);
-                    Ok::<Option<(BitcoinNews, bool)>, BitcoinAnalysisError>(Some((processed_news, is_positive)))
-                } else {
-                    tracing::debug!("Пропущена новость из-за пустого контента или заголовка: {}", news.title);
-                    Ok(None)
-                }
+                let result = self.huggingface_batch(model_url, &texts).await;
+                (positions, result)
             }
         }).collect();
 
@@ -65,105 +209,209 @@ impl DataProcessorService {
             .collect::<Vec<_>>()
             .await;
 
-        self.news_holder.clear().await?;
-        for result in results {
-            if let Some((news, is_positive)) = result? {
-                if (price_increased && is_positive) || (!price_increased && !is_positive) {
-                    self.news_holder.add(news).await?;
+        let mut scored: Vec<Vec<(SentimentPrediction, usize)>> = vec![Vec::new(); inputs.len()];
+        for (positions, result) in results {
+            for (position, prediction) in positions.into_iter().zip(result?) {
+                let (index, window) = &windows[position];
+                if let Some(prediction) = prediction {
+                    scored[*index].push((prediction, window.tokens));
                 }
             }
         }
 
-        let len = self.news_holder.len().await?;
-        tracing::info!("Обработано {} новостей", len);
-        Ok(())
+        // Статьи без единой оценки модели (ошибка API) оцениваем словарём
+        let predictions = inputs.iter().zip(&scored).map(|(&(text, language), windows)| {
+            if text.trim().is_empty() {
+                return SentimentPrediction::neutral();
+            }
+            SentimentPrediction::aggregate(windows).unwrap_or_else(|| self.lexicon_sentiment(text, language))
+        }).collect();
+        Ok(predictions)
     }
 
-    fn clean_text(&self, text: &str) -> Result<String> {
-        let html_regex = Regex::new(r"<[^>]+>")?;
-        let url_regex = Regex::new(r"http\S+|www\.\S+")?;
-        let whitespace_regex = Regex::new(r"\s+")?;
-
-        let cleaned = html_regex.replace_all(text, " ");
-        let cleaned = url_regex.replace_all(&cleaned, " ");
-        let cleaned = whitespace_regex.replace_all(&cleaned, " ");
-
-        Ok(cleaned.trim().to_string())
+    fn model_url(&self, language: Option<&str>) -> &str {
+        language
+            .and_then(|l| self.config.languages.huggingface_models.get(l))
+            .unwrap_or(&self.config.huggingface_api_url)
     }
 
-    async fn analyze_sentiment(&self, text: &str) -> Result<bool> {
-        if text.trim().is_empty() {
-            return Ok(false);
+    /// Один запрос с массивом текстов; None в ответе — статья, для которой нужен словарный анализ
+    async fn huggingface_batch(&self, model_url: &str, texts: &[String]) -> Result<Vec<Option<SentimentPrediction>>> {
+        // truncation — страховка на случай, если оценка длины окна окажется заниженной
+        let body = json!({ "inputs": texts, "parameters": { "truncation": true } });
+        let Some(result) = self.post_huggingface(model_url, &body).await? else {
+            return Ok(vec![None; texts.len()]);
+        };
+        let predictions = parse_batch_response(&result, texts.len());
+        if predictions.iter().all(Option::is_none) {
+            tracing::warn!("Некорректный формат ответа от Hugging Face: {:?}", result);
         }
+        Ok(predictions)
+    }
 
-        let max_len = 512;
-        let truncated_text: String = text
-            .split_whitespace()
-            .take_while(|word| max_len >= word.len() + 1)
-            .collect::<Vec<&str>>()
-            .join(" ");
+    /// POST в Inference API. Пока модель загружается (503 с `estimated_time`), ждём и повторяем;
+    /// None — API так и не вернул успешный ответ
+    async fn post_huggingface(&self, model_url: &str, payload: &Value) -> Result<Option<Value>> {
+        let settings = &self.config.sentiment.huggingface;
+        let mut attempt = 0;
 
-        let payload = json!({ "inputs": truncated_text });
+        loop {
+            let response = self
+                .client
+                .post(model_url)
+                .header("Authorization", format!("Bearer {}", self.config.huggingface_api_key))
+                .header("Content-Type", "application/json")
+                .json(payload)
+                .send()
+                .await?;
 
-        let response = self
-            .client
-            .post(&self.config.huggingface_api_url)
-            .header("Authorization", format!("Bearer {}", self.config.huggingface_api_key))
-            .header("Content-Type", "application/json")
-            .json(&payload)
-            .send()
-            .await?;
+            let status = response.status();
+            if status == StatusCode::SERVICE_UNAVAILABLE && attempt < settings.max_retries {
+                attempt += 1;
+                let body: Value = response.json().await.unwrap_or(Value::Null);
+                let wait = body["estimated_time"]
+                    .as_f64()
+                    .unwrap_or(DEFAULT_RETRY_DELAY_SECS)
+                    .clamp(1.0, settings.max_wait_secs as f64);
+                tracing::info!(
+                    "Модель Hugging Face загружается, повтор через {:.0} с (попытка {}/{})",
+                    wait, attempt, settings.max_retries
+                );
+                tokio::time::sleep(Duration::from_secs_f64(wait)).await;
+                continue;
+            }
 
-        if !response.status().is_success() {
-            tracing::warn!("Hugging Face API вернул ошибку: {}", response.status());
-            return Ok(self.simple_sentiment_analysis(text));
+            if !status.is_success() {
+                tracing::warn!("Hugging Face API вернул ошибку: {}", status);
+                return Ok(None);
+            }
+            return Ok(Some(response.json().await?));
         }
+    }
 
-        let result: Value = response.json().await?;
-        match result.as_array().and_then(|arr| arr.first()).and_then(|pred| pred["label"].as_str()) {
-            Some(label) => {
-                tracing::debug!("Hugging Face вернул метку: {}", label);
-                Ok(label.to_lowercase().contains("positive"))
-            }
-            None => {
-                tracing::warn!("Некорректный формат ответа от Hugging Face: {:?}", result);
-                Ok(self.simple_sentiment_analysis(text))
+    /// Аспекты каждой статьи. Настроение аспекта — словарная оценка предложений, где он упомянут;
+    /// без словарного сигнала берётся оценка всей статьи
+    async fn tag_aspects(&self, prepared: &[(BitcoinNews, String)], predictions: &[SentimentPrediction]) -> Result<Vec<Vec<AspectSentiment>>> {
+        let mut tagged: Vec<Vec<AspectSentiment>> = prepared.iter().zip(predictions)
+            .map(|((news, text), prediction)| {
+                self.aspects.classify(text).into_iter().map(|found| {
+                    let local = self.lexicons.analyze(&found.sentences.join(". "), news.language.as_deref()).compound;
+                    AspectSentiment {
+                        aspect: found.aspect,
+                        score: if local != 0.0 { local } else { prediction.score },
+                        mentions: found.mentions,
+                    }
+                }).collect()
+            })
+            .collect();
+
+        let zero_shot_url = self.config.aspects.zero_shot_model_url.as_deref()
+            .filter(|_| !self.config.huggingface_api_key.is_empty());
+        let Some(model_url) = zero_shot_url else {
+            return Ok(tagged);
+        };
+
+        // Статьи без ключевых слов отдаём zero-shot классификатору
+        let tasks: Vec<_> = tagged.iter().enumerate()
+            .filter(|(_, aspects)| aspects.is_empty())
+            .map(|(index, _)| {
+                let text = split_into_windows(&prepared[index].1, &self.config.sentiment.chunking)
+                    .into_iter()
+                    .next()
+                    .map(|window| window.text)
+                    .unwrap_or_default();
+                async move { (index, self.zero_shot_aspects(model_url, &text).await) }
+            })
+            .collect();
+        let results = stream::iter(tasks)
+            .buffer_unordered(self.config.max_concurrent_requests.unwrap_or(10))
+            .collect::<Vec<_>>()
+            .await;
+
+        for (index, result) in results {
+            for aspect in result? {
+                tagged[index].push(AspectSentiment { aspect, score: predictions[index].score, mentions: 0 });
             }
         }
+        Ok(tagged)
     }
 
-    fn simple_sentiment_analysis(&self, text: &str) -> bool {
-        let positive_words = [
-            "good", "great", "excellent", "amazing", "wonderful", "fantastic",
-            "positive", "bullish", "surge", "rally", "gain", "profit", "rise",
-            "increase", "growth", "boom", "success", "breakthrough", "adoption",
-            "institutional", "mainstream", "купить", "рост", "позитивный",
-        ];
-
-        let negative_words = [
-            "bad", "terrible", "awful", "horrible", "negative", "bearish",
-            "crash", "dump", "loss", "fall", "decline", "drop", "collapse",
-            "ban", "regulation", "scam", "hack", "theft", "продать", "падение",
-            "негативный", "кризис", "запрет",
-        ];
-
-        let negation_words = ["not", "never", "нет", "никогда"];
-        let text_lower = text.to_lowercase();
-        let words: Vec<&str> = text_lower.split_whitespace().collect();
-
-        let mut positive_count = 0;
-        let mut negative_count = 0;
-
-        for (i, word) in words.iter().enumerate() {
-            let is_negated = i > 0 && negation_words.contains(&words[i - 1]);
-            if positive_words.contains(word) {
-                positive_count += if is_negated { -1 } else { 1 };
+    async fn zero_shot_aspects(&self, model_url: &str, text: &str) -> Result<Vec<String>> {
+        if text.is_empty() {
+            return Ok(Vec::new());
+        }
+        let payload = json!({
+            "inputs": text,
+            "parameters": { "candidate_labels": self.aspects.aspect_names(), "multi_label": true },
+        });
+        let Some(result) = self.post_huggingface(model_url, &payload).await? else {
+            return Ok(Vec::new());
+        };
+
+        // Классический формат {labels: [...], scores: [...]} или список {label, score}
+        let labels: Vec<(String, f64)> = match (result["labels"].as_array(), result["scores"].as_array()) {
+            (Some(labels), Some(scores)) => labels.iter().zip(scores)
+                .filter_map(|(label, score)| Some((label.as_str()?.to_string(), score.as_f64()?)))
+                .collect(),
+            _ => result.as_array().into_iter().flatten()
+                .filter_map(|pred| Some((pred["label"].as_str()?.to_string(), pred["score"].as_f64()?)))
+                .collect(),
+        };
+        Ok(labels.into_iter()
+            .filter(|(_, score)| *score >= self.config.aspects.zero_shot_threshold)
+            .map(|(label, _)| label)
+            .collect())
+    }
+
+    /// Инференс локальной модели в пуле блокирующих задач; при ошибке — словарный анализ
+    #[cfg(feature = "onnx")]
+    async fn onnx_sentiment(&self, text: &str, language: Option<&str>) -> SentimentPrediction {
+        let Some(model) = self.onnx_model.clone() else {
+            return self.lexicon_sentiment(text, language);
+        };
+        let input = text.to_string();
+        match tokio::task::spawn_blocking(move || model.predict(&input)).await {
+            Ok(Ok(prediction)) => {
+                tracing::debug!("ONNX-модель вернула оценку {:.3}", prediction.score);
+                prediction
             }
-            if negative_words.contains(word) {
-                negative_count += if is_negated { -1 } else { 1 };
+            Ok(Err(e)) => {
+                tracing::warn!("Ошибка инференса ONNX-модели: {}", e);
+                self.lexicon_sentiment(text, language)
+            }
+            Err(e) => {
+                tracing::warn!("Задача инференса ONNX-модели прервана: {}", e);
+                self.lexicon_sentiment(text, language)
             }
         }
+    }
 
-        positive_count > negative_count
+    #[cfg(not(feature = "onnx"))]
+    async fn onnx_sentiment(&self, text: &str, language: Option<&str>) -> SentimentPrediction {
+        self.lexicon_sentiment(text, language)
     }
-}
\ No newline at end of file
+
+    fn lexicon_sentiment(&self, text: &str, language: Option<&str>) -> SentimentPrediction {
+        self.lexicons.analyze(text, language).into()
+    }
+}
+
+/// Ответ на пакет — массив списков `{label, score}` в порядке входов;
+/// на одиночный вход API может вернуть плоский список
+fn parse_batch_response(result: &Value, expected: usize) -> Vec<Option<SentimentPrediction>> {
+    let parse_labels = |predictions: &Value| {
+        let labels: Vec<(String, f64)> = predictions.as_array()?
+            .iter()
+            .filter_map(|pred| Some((pred["label"].as_str()?.to_lowercase(), pred["score"].as_f64().unwrap_or(0.0))))
+            .collect();
+        SentimentPrediction::from_labels(&labels)
+    };
+
+    match result.as_array() {
+        Some(items) if items.first().is_some_and(Value::is_array) && items.len() == expected => {
+            items.iter().map(parse_labels).collect()
+        }
+        Some(items) if expected == 1 && items.first().is_some_and(Value::is_object) => vec![parse_labels(result)],
+        _ => vec![None; expected],
+    }
+}
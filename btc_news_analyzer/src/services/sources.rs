@@ -0,0 +1,282 @@
+use chrono::{DateTime, NaiveDate, NaiveDateTime, NaiveTime, Utc};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Client, ClientBuilder};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::analytics::{detect_language, language_code_from_name};
+use crate::config::{GdeltConfig, JsonSourceConfig};
+use crate::errors::{BitcoinAnalysisError, Result};
+use crate::models::BitcoinNews;
+
+// Меньшие числа — скорее год или дата "YYYYMMDD", чем unix-время
+const MIN_PLAUSIBLE_TIMESTAMP: f64 = 1e9;
+
+/// Статьи GDELT DOC 2.0 API (секция `[gdelt]`): ссылка, заголовок, дата, домен и тон.
+/// Ключ не нужен; текст статьи GDELT не отдаёт, его может загрузить `ArticleFetcher`
+#[derive(Clone)]
+pub struct GdeltSource {
+    client: Client,
+    config: GdeltConfig,
+}
+
+impl GdeltSource {
+    pub fn new(config: GdeltConfig) -> Self {
+        let client = ClientBuilder::new()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .build()
+            .expect("Failed to build reqwest client");
+        GdeltSource { client, config }
+    }
+
+    /// Статьи по запросу в синтаксисе GDELT (`Query::to_gdelt`) за последние `days` дней
+    pub async fn fetch(&self, query: &str, days: u32) -> Result<Vec<BitcoinNews>> {
+        let days = days.clamp(1, self.config.max_history_days);
+        let json = self.request(query, "ArtList", days).await?;
+        // Без результатов GDELT возвращает пустой объект
+        let articles = json["articles"].as_array().cloned().unwrap_or_default();
+
+        let tones = if self.config.fetch_tone && !articles.is_empty() {
+            match self.request(query, "ToneChart", days).await {
+                Ok(json) => tone_by_url(&json),
+                Err(e) => {
+                    tracing::debug!("GDELT: тон статей недоступен: {}", e);
+                    HashMap::new()
+                }
+            }
+        } else {
+            HashMap::new()
+        };
+
+        Ok(articles.iter()
+            .filter_map(|article| {
+                let title = article["title"].as_str()?.trim().to_string();
+                let url = article["url"].as_str()?.to_string();
+                let language = article["language"].as_str()
+                    .and_then(language_code_from_name)
+                    .or_else(|| detect_language(&title));
+                Some(BitcoinNews {
+                    title,
+                    content: String::new(),
+                    is_positive: None,
+                    published_at: article.get("seendate").and_then(parse_published),
+                    language,
+                    source: article["domain"].as_str().map(str::to_string),
+                    source_tone: tones.get(&url).copied(),
+                    url: Some(url),
+                    ..Default::default()
+                })
+            })
+            .collect())
+    }
+
+    async fn request(&self, query: &str, mode: &str, days: u32) -> Result<Value> {
+        let url = format!(
+            "{}?query={}&mode={}&format=json&maxrecords={}&timespan={}d&sort=DateDesc",
+            self.config.base_url,
+            urlencoding::encode(query),
+            mode,
+            self.config.max_records,
+            days
+        );
+        tracing::info!("Запрос GDELT ({}) за {} дней", mode, days);
+
+        let response = self.client.get(&url).send().await?;
+        let status = response.status();
+        let body = response.text().await?;
+        if !status.is_success() {
+            return Err(BitcoinAnalysisError::ApiError(format!("GDELT: статус {}", status)));
+        }
+        // Ошибки запроса GDELT возвращает текстом со статусом 200
+        serde_json::from_str(&body).map_err(|_| {
+            BitcoinAnalysisError::InvalidDataFormat(format!("GDELT: {}", body.trim().chars().take(200).collect::<String>()))
+        })
+    }
+}
+
+/// Тон статей из ToneChart: статьи сгруппированы по целому значению тона (bin)
+fn tone_by_url(json: &Value) -> HashMap<String, f64> {
+    let mut tones = HashMap::new();
+    for bin in json["tonechart"].as_array().into_iter().flatten() {
+        let Some(tone) = bin["bin"].as_f64() else { continue };
+        for article in bin["toparts"].as_array().into_iter().flatten() {
+            if let Some(url) = article["url"].as_str() {
+                tones.insert(url.to_string(), tone);
+            }
+        }
+    }
+    tones
+}
+
+/// Новости произвольного JSON API по путям к полям из секции `[[json_sources]]`
+#[derive(Clone)]
+pub struct JsonNewsSource {
+    client: Client,
+    config: JsonSourceConfig,
+}
+
+impl JsonNewsSource {
+    pub fn new(config: JsonSourceConfig) -> Result<Self> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &config.headers {
+            let name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|e| BitcoinAnalysisError::InvalidDataFormat(format!("заголовок {}: {}", name, e)))?;
+            let value = HeaderValue::from_str(value)
+                .map_err(|e| BitcoinAnalysisError::InvalidDataFormat(format!("заголовок {}: {}", name, e)))?;
+            headers.insert(name, value);
+        }
+        let client = ClientBuilder::new()
+            .timeout(Duration::from_secs(config.timeout_secs))
+            .default_headers(headers)
+            .build()?;
+        Ok(JsonNewsSource { client, config })
+    }
+
+    /// Новости источника; `query` подставляется в `{query}` адреса
+    pub async fn fetch(&self, query: &str, from_date: NaiveDate, days: u32) -> Result<Vec<BitcoinNews>> {
+        let config = &self.config;
+        let url = config.url
+            .replace("{query}", &urlencoding::encode(query))
+            .replace("{from}", &from_date.format("%Y-%m-%d").to_string())
+            .replace("{days}", &days.to_string());
+        tracing::info!("Запрос новостей из источника {}", config.name);
+
+        let response = self.client.get(&url).send().await?;
+        let status = response.status();
+        if !status.is_success() {
+            return Err(BitcoinAnalysisError::ApiError(format!("{}: статус {}", config.name, status)));
+        }
+        let json: Value = response.json().await?;
+
+        let items = json_path(&json, &config.items_path)
+            .and_then(Value::as_array)
+            .ok_or_else(|| BitcoinAnalysisError::InvalidDataFormat(format!("{}: нет массива по пути '{}'", config.name, config.items_path)))?;
+
+        let text_at = |item: &Value, path: &Option<String>| {
+            path.as_deref()
+                .and_then(|path| json_path(item, path))
+                .and_then(Value::as_str)
+                .map(|text| text.trim().to_string())
+                .filter(|text| !text.is_empty())
+        };
+
+        Ok(items.iter()
+            .take(config.max_items)
+            .filter_map(|item| {
+                let title = text_at(item, &Some(config.title_path.clone()))?;
+                let content = text_at(item, &config.content_path).unwrap_or_default();
+                let language = detect_language(&format!("{} {}", title, content));
+                Some(BitcoinNews {
+                    title,
+                    content,
+                    is_positive: None,
+                    url: text_at(item, &config.url_path),
+                    published_at: config.published_path.as_deref()
+                        .and_then(|path| json_path(item, path))
+                        .and_then(parse_published),
+                    language,
+                    source: text_at(item, &config.source_path),
+                    source_tone: config.tone_path.as_deref()
+                        .and_then(|path| json_path(item, path))
+                        .and_then(|value| value.as_f64().or_else(|| value.as_str()?.trim().parse().ok())),
+                    ..Default::default()
+                })
+            })
+            .collect())
+    }
+}
+
+/// Значение по пути через точку; числовые сегменты — индексы массивов. Пустой путь — сам документ
+pub fn json_path<'a>(value: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .filter(|segment| !segment.is_empty())
+        .try_fold(value, |current, segment| match current {
+            Value::Array(items) => items.get(segment.parse::<usize>().ok()?),
+            _ => current.get(segment),
+        })
+}
+
+/// Дата публикации в RFC 3339: RFC 3339, RFC 2822, "YYYYMMDDTHHMMSSZ" (GDELT),
+/// "YYYY-MM-DD HH:MM:SS", "YYYY-MM-DD" и "YYYYMMDD" (UTC) или unix-время в секундах либо
+/// миллисекундах. Форматы дат проверяются первыми, а число считается unix-временем только
+/// начиная с 1e9 (сентябрь 2001), чтобы "2024" или "20240301" не превращались в 1970 год
+pub(crate) fn parse_published(value: &Value) -> Option<String> {
+    let from_timestamp = |timestamp: f64| {
+        if !timestamp.is_finite() || timestamp < MIN_PLAUSIBLE_TIMESTAMP {
+            return None;
+        }
+        let seconds = if timestamp > 1e12 { timestamp / 1000.0 } else { timestamp };
+        DateTime::from_timestamp(seconds as i64, 0)
+    };
+    let datetime = match value {
+        Value::Number(number) => from_timestamp(number.as_f64()?),
+        Value::String(text) => {
+            let text = text.trim();
+            let date = |format: &str| NaiveDate::parse_from_str(text, format).ok().map(|d| d.and_time(NaiveTime::MIN).and_utc());
+            DateTime::parse_from_rfc3339(text)
+                .or_else(|_| DateTime::parse_from_rfc2822(text))
+                .map(|datetime| datetime.with_timezone(&Utc))
+                .ok()
+                .or_else(|| NaiveDateTime::parse_from_str(text, "%Y%m%dT%H%M%SZ").ok().map(|d| d.and_utc()))
+                .or_else(|| NaiveDateTime::parse_from_str(text, "%Y-%m-%d %H:%M:%S").ok().map(|d| d.and_utc()))
+                .or_else(|| date("%Y-%m-%d"))
+                .or_else(|| (text.len() == 8).then(|| date("%Y%m%d")).flatten())
+                .or_else(|| text.parse::<f64>().ok().and_then(from_timestamp))
+        }
+        _ => None,
+    }?;
+    Some(datetime.to_rfc3339())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn parsed(value: Value) -> Option<String> {
+        parse_published(&value)
+    }
+
+    #[test]
+    fn date_formats_are_normalized_to_rfc3339() {
+        assert_eq!(parsed(json!("2025-08-20T13:15:00+03:00")).as_deref(), Some("2025-08-20T10:15:00+00:00"));
+        assert_eq!(parsed(json!("Wed, 20 Aug 2025 10:15:00 GMT")).as_deref(), Some("2025-08-20T10:15:00+00:00"));
+        assert_eq!(parsed(json!("20250820T101500Z")).as_deref(), Some("2025-08-20T10:15:00+00:00"));
+        assert_eq!(parsed(json!(" 2025-08-20 10:15:00 ")).as_deref(), Some("2025-08-20T10:15:00+00:00"));
+        assert_eq!(parsed(json!("2025-08-20")).as_deref(), Some("2025-08-20T00:00:00+00:00"));
+        assert_eq!(parsed(json!("20240301")).as_deref(), Some("2024-03-01T00:00:00+00:00"));
+    }
+
+    #[test]
+    fn unix_time_in_seconds_or_milliseconds() {
+        assert_eq!(parsed(json!(1755684900)).as_deref(), Some("2025-08-20T10:15:00+00:00"));
+        assert_eq!(parsed(json!(1755684900000u64)).as_deref(), Some("2025-08-20T10:15:00+00:00"));
+        assert_eq!(parsed(json!("1755684900")).as_deref(), Some("2025-08-20T10:15:00+00:00"));
+        assert_eq!(parsed(json!(1755684900.5)).as_deref(), Some("2025-08-20T10:15:00+00:00"));
+    }
+
+    #[test]
+    fn implausible_numbers_are_not_dates() {
+        for value in [json!("2024"), json!(2024), json!(20240301), json!("99999999"), json!(0), json!(-1755684900), json!("1e3")] {
+            assert_eq!(parsed(value.clone()), None, "{}", value);
+        }
+    }
+
+    #[test]
+    fn unparseable_values_are_skipped() {
+        for value in [json!(""), json!("yesterday"), json!("2025-13-40"), json!(null), json!(true), json!({"date": "2025-08-20"})] {
+            assert_eq!(parsed(value.clone()), None, "{}", value);
+        }
+    }
+
+    #[test]
+    fn json_path_walks_objects_and_arrays() {
+        let document = json!({"data": {"items": [{"title": "a"}, {"title": "b"}]}});
+        assert_eq!(json_path(&document, "data.items.1.title"), Some(&json!("b")));
+        assert_eq!(json_path(&document, ""), Some(&document));
+        assert_eq!(json_path(&document, "data.items.x"), None);
+        assert_eq!(json_path(&document, "data.items.5"), None);
+        assert_eq!(json_path(&document, "missing.path"), None);
+    }
+}
@@ -1,7 +1,38 @@
 use btc_news_analyzer::*;
 use chrono::NaiveDate;
+#[allow(unused_imports, clippy::single_component_path_imports)]
 use tokio_test;
 
+fn test_config() -> AppConfig {
+    AppConfig {
+        newsapi_url: "https://newsapi.org/v2/everything".to_string(),
+        newsapi_key: "test_key".to_string(),
+        huggingface_api_url: "https://api-inference.huggingface.co/models/test".to_string(),
+        huggingface_api_key: "test_key".to_string(),
+        bitcoin_keywords: vec!["bitcoin".to_string(), "crypto".to_string()],
+        max_articles: Some(50),
+        max_concurrent_requests: Some(10),
+        sentiment_series_granularity: None,
+        decision: DecisionConfig::default(),
+        ranking: RankingConfig::default(),
+        dedup: DedupConfig::default(),
+        languages: LanguageConfig::default(),
+        sentiment: SentimentConfig::default(),
+        aspects: AspectConfig::default(),
+        entities: EntityConfig::default(),
+        article_fetch: ArticleFetchConfig::default(),
+        query: QueryConfig::default(),
+        relevance: RelevanceConfig::default(),
+        newsapi: NewsApiConfig::default(),
+        reddit: RedditConfig::default(),
+        news_sources: NewsSourcesConfig::default(),
+        gdelt: GdeltConfig::default(),
+        json_sources: Vec::new(),
+        files: FileSourcesConfig::default(),
+        endpoints: EndpointsConfig::default(),
+    }
+}
+
 #[tokio::test]
 async fn test_bitcoin_price_holder() {
     let holder = BitcoinPriceHolder::new();
@@ -40,6 +71,7 @@ async fn test_bitcoin_news_holder() {
         is_positive: Some(true),
         url: Some("https://example.com".to_string()),
         published_at: Some("2025-08-20T12:00:00Z".to_string()),
+        ..Default::default()
     };
     
     holder.add(news.clone()).await.unwrap();
@@ -64,7 +96,6 @@ async fn test_bitcoin_news_holder() {
 #[test]
 fn test_config_validation() {
     let mut config = AppConfig {
-        coindesk_api_url: "https://api.coindesk.com/v1/bpi/historical/close.json".to_string(),
         newsapi_url: "https://newsapi.org/v2/everything".to_string(),
         newsapi_key: "test_key".to_string(),
         huggingface_api_url: "https://api-inference.huggingface.co/models/test".to_string(),
@@ -72,6 +103,24 @@ fn test_config_validation() {
         bitcoin_keywords: vec!["bitcoin".to_string(), "crypto".to_string()],
         max_articles: Some(50),
         max_concurrent_requests: Some(10),
+        sentiment_series_granularity: None,
+        decision: DecisionConfig::default(),
+        ranking: RankingConfig::default(),
+        dedup: DedupConfig::default(),
+        languages: LanguageConfig::default(),
+        sentiment: SentimentConfig::default(),
+        aspects: AspectConfig::default(),
+        entities: EntityConfig::default(),
+        article_fetch: ArticleFetchConfig::default(),
+        query: QueryConfig::default(),
+        relevance: RelevanceConfig::default(),
+        newsapi: NewsApiConfig::default(),
+        reddit: RedditConfig::default(),
+        news_sources: NewsSourcesConfig::default(),
+        gdelt: GdeltConfig::default(),
+        json_sources: Vec::new(),
+        files: FileSourcesConfig::default(),
+        endpoints: EndpointsConfig::default(),
     };
     
     // Валидная конфигурация должна проходить
@@ -98,6 +147,33 @@ fn test_config_validation() {
     
     config.max_concurrent_requests = Some(100);
     assert!(config.validate().is_err());
+
+    // Пороги секции [decision] должны быть упорядочены
+    config.max_concurrent_requests = Some(10);
+    config.decision.moderate_price_change_pct = 6.0;
+    assert!(config.validate().is_err());
+
+    config.decision = DecisionConfig::default();
+    config.decision.price_weight = 1.5;
+    assert!(config.validate().is_err());
+}
+
+#[test]
+fn test_decision_overrides() {
+    let base = DecisionConfig::default();
+    let overrides = DecisionOverrides {
+        price_weight: Some(0.3),
+        news_weight: Some(0.7),
+        key_news_count: Some(10),
+        ..DecisionOverrides::default()
+    };
+
+    let effective = base.with_overrides(&overrides);
+    assert_eq!(effective.price_weight, 0.3);
+    assert_eq!(effective.news_weight, 0.7);
+    assert_eq!(effective.key_news_count, 10);
+    assert_eq!(effective.strong_price_change_pct, base.strong_price_change_pct);
+    assert!(effective.validate().is_ok());
 }
 
 #[tokio::test]
@@ -128,3 +204,996 @@ async fn test_multiple_prices_ordering() {
     assert_eq!(holder.start_price().await.unwrap(), Some(65000.0));
     assert_eq!(holder.end_price().await.unwrap(), Some(66000.0));
 }
+
+#[tokio::test]
+async fn test_returns_based_price_statistics() {
+    let price_holder = BitcoinPriceHolder::new();
+    let news_holder = BitcoinNewsHolder::new();
+    let amount_days = std::sync::Arc::new(tokio::sync::Mutex::new(AmountDays { days: 4 }));
+
+    // 100 -> 110 -> 99 -> 108.9: просадка от пика 110 до 99 составляет 10%
+    for (day, price) in [(17, 100.0), (18, 110.0), (19, 99.0), (20, 108.9)] {
+        price_holder.add(BitcoinPrice {
+            date: NaiveDate::from_ymd_opt(2025, 8, day).unwrap(),
+            price,
+        }).await.unwrap();
+    }
+
+    let decision = DataMakerDecisionService::new(price_holder, news_holder, amount_days, test_config());
+    let result = decision.make_decision().await.unwrap();
+    let stats = &result.price_statistics;
+
+    assert_eq!(stats.data_points, 4);
+    assert!((stats.max_drawdown - 10.0).abs() < 1e-9);
+    assert!((stats.price_range_percentage - (11.0 / 99.0 * 100.0)).abs() < 1e-9);
+
+    let returns = [1.1f64.ln(), 0.9f64.ln(), 1.1f64.ln()];
+    let mean = returns.iter().sum::<f64>() / 3.0;
+    let expected = (returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / 2.0).sqrt() * 100.0;
+    assert!((stats.daily_volatility - expected).abs() < 1e-9);
+    assert!((stats.annualized_volatility - expected * 365f64.sqrt()).abs() < 1e-9);
+    assert!((stats.average_abs_daily_change - 10.0).abs() < 1e-9);
+
+    // Резюме по умолчанию на английском
+    assert_eq!(result.language, "en");
+    assert!(result.summary.contains("rose by 8.90%"));
+}
+
+#[tokio::test]
+async fn test_backtest_long_flat_short() {
+    // Устойчивый рост: сигнал bullish, стратегия весь период в лонге
+    let prices: Vec<BitcoinPrice> = (0..15)
+        .map(|i| BitcoinPrice {
+            date: NaiveDate::from_ymd_opt(2025, 8, 1 + i).unwrap(),
+            price: 60000.0 * 1.01f64.powi(i as i32),
+        })
+        .collect();
+    let news: Vec<BitcoinNews> = (0..15)
+        .map(|i| BitcoinNews {
+            title: "Bitcoin rally".to_string(),
+            content: String::new(),
+            is_positive: Some(true),
+            url: None,
+            published_at: Some(format!("2025-08-{:02}T12:00:00Z", 1 + i)),
+            ..Default::default()
+        })
+        .collect();
+
+    let service = BacktestService::new(BitcoinPriceHolder::new(), BitcoinNewsHolder::new(), test_config());
+    let params = BacktestParams { window_days: 5, fee_bps: 10.0, allow_short: true };
+    // Нулевая и нечисловая цены из файла пропускаются, а не портят кривую капитала
+    let mut dirty_prices = prices.clone();
+    dirty_prices.push(BitcoinPrice { date: NaiveDate::from_ymd_opt(2025, 8, 16).unwrap(), price: 0.0 });
+    dirty_prices.push(BitcoinPrice { date: NaiveDate::from_ymd_opt(2025, 8, 17).unwrap(), price: f64::NAN });
+    let report = service.run(dirty_prices, news, &params).await.unwrap();
+    assert!(report.steps.iter().all(|s| s.equity.is_finite()));
+
+    assert_eq!(report.steps_count, 9);
+    assert_eq!(report.trades, 1);
+    assert!(report.steps.iter().all(|s| s.position == 1.0));
+    assert_eq!(report.hit_rate, Some(1.0));
+    let expected = ((1.01 - 0.001) * 1.01f64.powi(8) - 1.0) * 100.0;
+    assert!((report.total_return - expected).abs() < 1e-6);
+    assert!(report.max_drawdown < 0.2);
+
+    // Недостаточно истории для окна
+    let short_params = BacktestParams { window_days: 30, ..BacktestParams::default() };
+    assert!(service.run(prices, vec![], &short_params).await.is_err());
+}
+
+#[tokio::test]
+async fn test_backtest_short_liquidation() {
+    // Падение с негативным фоном держит стратегию в шорте, затем цена вырастает в 2.5 раза
+    let mut prices: Vec<BitcoinPrice> = (0..10)
+        .map(|i| BitcoinPrice {
+            date: NaiveDate::from_ymd_opt(2025, 8, 1 + i).unwrap(),
+            price: 60000.0 * 0.98f64.powi(i as i32),
+        })
+        .collect();
+    let last = prices[9].price;
+    for (i, factor) in [2.5, 2.6, 2.7].into_iter().enumerate() {
+        prices.push(BitcoinPrice { date: NaiveDate::from_ymd_opt(2025, 8, 11 + i as u32).unwrap(), price: last * factor });
+    }
+    let news: Vec<BitcoinNews> = (0..13)
+        .map(|i| BitcoinNews {
+            title: "Bitcoin crash".to_string(),
+            is_positive: Some(false),
+            published_at: Some(format!("2025-08-{:02}T12:00:00Z", 1 + i)),
+            ..Default::default()
+        })
+        .collect();
+
+    let service = BacktestService::new(BitcoinPriceHolder::new(), BitcoinNewsHolder::new(), test_config());
+    let params = BacktestParams { window_days: 5, fee_bps: 0.0, allow_short: true };
+    let report = service.run(prices, news, &params).await.unwrap();
+
+    assert!(report.liquidated);
+    assert_eq!(report.steps_count, 5);
+    assert_eq!(report.end_date, NaiveDate::from_ymd_opt(2025, 8, 11).unwrap());
+    assert!(report.steps.iter().all(|s| s.position == -1.0));
+    assert_eq!(report.steps.last().unwrap().equity, 0.0);
+    assert_eq!(report.total_return, -100.0);
+    assert_eq!(report.annualized_return, -100.0);
+    assert_eq!(report.max_drawdown, 100.0);
+}
+
+#[test]
+fn test_key_news_ranking() {
+    use btc_news_analyzer::analytics::rank_news;
+
+    let news = |title: &str, url: &str, published_at: &str, confidence: Option<f64>| BitcoinNews {
+        title: title.to_string(),
+        content: "Markets react to the latest bitcoin headlines".to_string(),
+        is_positive: Some(true),
+        url: Some(url.to_string()),
+        published_at: Some(published_at.to_string()),
+        sentiment_confidence: confidence,
+        ..Default::default()
+    };
+    let news_items = vec![
+        news("Celebrity buys a yacht", "https://gossip.example/yacht", "2025-08-18T08:00:00Z", None),
+        news("SEC approves spot Bitcoin ETF", "https://www.coindesk.com/etf", "2025-08-20T10:00:00Z", Some(0.95)),
+        news("SEC approves spot Bitcoin ETF filings", "https://decrypt.co/etf", "2025-08-20T09:00:00Z", Some(0.9)),
+    ];
+
+    let mut config = RankingConfig::default();
+    config.source_trust.insert("coindesk.com".to_string(), 0.9);
+    let keywords = vec!["bitcoin".to_string(), "etf".to_string()];
+
+    // Без дедупликации сюжет определяется по сходству заголовков
+    let no_dedup = DedupConfig { enabled: false, ..Default::default() };
+    let ranked = rank_news(&news_items, &keywords, &config, &no_dedup);
+    assert_eq!(ranked.len(), 3);
+    assert_eq!(ranked[0].index, 1);
+    assert_eq!(ranked[2].index, 0);
+    assert!(ranked[0].score > ranked[1].score);
+    assert!(ranked[0].reasons.iter().any(|r| r.contains("trusted source coindesk.com")));
+    assert!(ranked[0].reasons.iter().any(|r| r.contains("1 other outlets")));
+    assert_eq!(ranked[2].confidence, 0.5);
+
+    // После дедупликации копии свёрнуты в одну новость: повторяемость — из duplicate_count и story_id
+    let mut deduplicated = vec![news_items[0].clone(), news_items[1].clone()];
+    deduplicated[0].story_id = Some(0);
+    deduplicated[1].story_id = Some(1);
+    deduplicated[1].duplicate_count = 2;
+    let ranked = rank_news(&deduplicated, &keywords, &config, &DedupConfig::default());
+    assert_eq!(ranked[0].index, 1);
+    assert!(ranked[0].reasons.iter().any(|r| r.contains("2 other outlets")));
+    assert!(!ranked[1].reasons.iter().any(|r| r.contains("other outlets")));
+}
+
+#[tokio::test]
+async fn test_language_detection_and_breakdown() {
+    use btc_news_analyzer::analytics::detect_language;
+
+    assert_eq!(
+        detect_language("Bitcoin price rallies as institutional investors pour money into spot ETFs").as_deref(),
+        Some("en")
+    );
+    assert_eq!(
+        detect_language("Биткоин вырос на фоне притока средств в спотовые биржевые фонды").as_deref(),
+        Some("ru")
+    );
+    assert_eq!(detect_language("BTC"), None);
+
+    let price_holder = BitcoinPriceHolder::new();
+    for (day, price) in [(18, 60000.0), (19, 61000.0)] {
+        price_holder.add(BitcoinPrice { date: NaiveDate::from_ymd_opt(2025, 8, day).unwrap(), price }).await.unwrap();
+    }
+    let news_holder = BitcoinNewsHolder::new();
+    for (language, is_positive) in [(Some("en"), true), (Some("en"), false), (Some("ru"), true), (None, true)] {
+        news_holder.add(BitcoinNews {
+            title: "Bitcoin".to_string(),
+            is_positive: Some(is_positive),
+            language: language.map(|l| l.to_string()),
+            ..Default::default()
+        }).await.unwrap();
+    }
+
+    let amount_days = std::sync::Arc::new(tokio::sync::Mutex::new(AmountDays { days: 2 }));
+    let decision = DataMakerDecisionService::new(price_holder, news_holder, amount_days, test_config());
+    let by_language = decision.make_decision().await.unwrap().news_statistics.by_language;
+
+    assert_eq!(by_language.len(), 3);
+    assert_eq!(by_language[0].language, "en");
+    assert_eq!(by_language[0].total, 2);
+    assert_eq!(by_language[0].sentiment_score, 0.0);
+    assert!(by_language.iter().any(|l| l.language == "unknown" && l.positive_count == 1));
+}
+
+#[tokio::test]
+async fn test_neutral_sentiment_is_counted_as_neutral() {
+    let mut config = test_config();
+    config.huggingface_api_key = String::new();
+
+    let price_holder = BitcoinPriceHolder::new();
+    for (day, price) in [(18, 60000.0), (19, 61000.0)] {
+        price_holder.add(BitcoinPrice { date: NaiveDate::from_ymd_opt(2025, 8, day).unwrap(), price }).await.unwrap();
+    }
+    let news_holder = BitcoinNewsHolder::new();
+    for title in ["Bitcoin rally extends as ETF inflows surge", "The Bitcoin block was mined at noon"] {
+        news_holder.add(BitcoinNews {
+            title: title.to_string(),
+            language: Some("en".to_string()),
+            published_at: Some("2025-08-19T12:00:00Z".to_string()),
+            ..Default::default()
+        }).await.unwrap();
+    }
+
+    DataProcessorService::new(price_holder.clone(), news_holder.clone(), config.clone())
+        .process_data().await.unwrap();
+    let processed = news_holder.get().await.unwrap();
+    assert_eq!(processed.len(), 2);
+    assert_eq!(processed[1].is_positive, None);
+
+    let amount_days = std::sync::Arc::new(tokio::sync::Mutex::new(AmountDays { days: 2 }));
+    let result = DataMakerDecisionService::new(price_holder, news_holder, amount_days, config)
+        .make_decision().await.unwrap();
+    assert_eq!(result.news_statistics.positive_count, 1);
+    assert_eq!(result.news_statistics.negative_count, 0);
+    assert_eq!(result.news_statistics.neutral_count, 1);
+}
+
+#[test]
+fn test_onnx_engine_config_validation() {
+    let mut config = test_config();
+    config.sentiment.engine = SentimentEngine::Onnx;
+    // Без пути к модели движок onnx невалиден при любой сборке
+    assert!(config.validate().is_err());
+
+    config.sentiment.onnx.model_path = Some("models/sentiment/model.onnx".to_string());
+    assert_eq!(config.validate().is_ok(), cfg!(feature = "onnx"));
+    assert_eq!(
+        config.sentiment.onnx.tokenizer_path(),
+        Some(std::path::PathBuf::from("models/sentiment/tokenizer.json"))
+    );
+    assert!(!config.sentiment.uses_huggingface(&config.huggingface_api_key));
+}
+
+#[tokio::test]
+async fn test_huggingface_batching_and_model_loading_retry() {
+    use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::post, Json, Router};
+    use serde_json::{json, Value};
+    use std::sync::{Arc, Mutex};
+
+    // Мок Inference API: первый запрос — 503 «модель загружается», дальше — оценки по пакету
+    let batches: Arc<Mutex<Vec<usize>>> = Arc::default();
+    async fn inference(State(batches): State<Arc<Mutex<Vec<usize>>>>, Json(body): Json<Value>) -> axum::response::Response {
+        let inputs = body["inputs"].as_array().cloned().unwrap_or_default();
+        let first_call = {
+            let mut batches = batches.lock().unwrap();
+            batches.push(inputs.len());
+            batches.len() == 1
+        };
+        if first_call {
+            return (StatusCode::SERVICE_UNAVAILABLE, Json(json!({"error": "Model is loading", "estimated_time": 0.2}))).into_response();
+        }
+        let scores: Vec<Value> = inputs.iter().map(|text| {
+            let positive = if text.as_str().unwrap_or_default().contains("rally") { 0.9 } else { 0.1 };
+            json!([{"label": "positive", "score": positive}, {"label": "negative", "score": 1.0 - positive}])
+        }).collect();
+        Json(Value::Array(scores)).into_response()
+    }
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    let app = Router::new().route("/model", post(inference)).with_state(batches.clone());
+    tokio::spawn(axum::Server::from_tcp(listener).unwrap().serve(app.into_make_service()));
+
+    let mut config = test_config();
+    config.huggingface_api_url = format!("http://{}/model", address);
+    config.sentiment.huggingface.batch_size = 2;
+
+    let price_holder = BitcoinPriceHolder::new();
+    for (day, price) in [(18, 60000.0), (19, 61000.0)] {
+        price_holder.add(BitcoinPrice { date: NaiveDate::from_ymd_opt(2025, 8, day).unwrap(), price }).await.unwrap();
+    }
+    let news_holder = BitcoinNewsHolder::new();
+    let titles = [
+        "Bitcoin rally extends to new weekly high",
+        "Miners capitulate as hashprice collapses",
+        "Altcoins rally behind bitcoin breakout",
+        "Exchange outage freezes withdrawals",
+        "Analysts expect the rally to continue into autumn",
+    ];
+    for title in titles {
+        news_holder.add(BitcoinNews {
+            title: title.to_string(),
+            content: title.to_string(),
+            language: Some("en".to_string()),
+            ..Default::default()
+        }).await.unwrap();
+    }
+
+    let processor = DataProcessorService::new(price_holder, news_holder.clone(), config);
+    processor.process_data().await.unwrap();
+
+    // 5 статей пакетами по 2 → 3 запроса плюс повтор после 503
+    let mut sizes = batches.lock().unwrap().clone();
+    sizes.sort_unstable();
+    assert_eq!(sizes, vec![1, 2, 2, 2]);
+    let processed = news_holder.get().await.unwrap();
+    assert_eq!(processed.len(), 3);
+    assert!(processed.iter().all(|news| news.title.contains("rally")));
+    assert!(processed.iter().all(|news| (news.sentiment_score.unwrap() - 0.8).abs() < 1e-9));
+}
+
+#[tokio::test]
+async fn test_aspect_tagging_and_summary() {
+    use btc_news_analyzer::analytics::{summarize_aspects, AspectClassifier};
+
+    let classifier = AspectClassifier::new(&AspectConfig::default().taxonomy);
+    let found = classifier.classify(
+        "SEC delays decision on new spot ETF filings. Exchange hacked, funds stolen! Inflows continue.",
+    );
+    let aspects: Vec<&str> = found.iter().map(|m| m.aspect.as_str()).collect();
+    assert_eq!(aspects, vec!["etf", "regulation", "security"]);
+    assert_eq!(found[0].mentions, 2); // "ETF" и "Inflows"
+    assert_eq!(found[2].sentences, vec!["Exchange hacked, funds stolen".to_string()]);
+    // Фразы и стемы: "interest rates" совпадает, "bank" не считается запретом ("ban")
+    assert_eq!(classifier.classify("Fed keeps interest rates unchanged")[0].aspect, "macro");
+    assert!(classifier.classify("The bank opened a new branch").is_empty());
+
+    // Процессор: аспект получает оценку предложений, где он упомянут
+    let mut config = test_config();
+    config.huggingface_api_key.clear();
+    let price_holder = BitcoinPriceHolder::new();
+    for (day, price) in [(18, 60000.0), (19, 61000.0)] {
+        price_holder.add(BitcoinPrice { date: NaiveDate::from_ymd_opt(2025, 8, day).unwrap(), price }).await.unwrap();
+    }
+    let news_holder = BitcoinNewsHolder::new();
+    news_holder.add(BitcoinNews {
+        title: "Spot ETF inflows surge to a record".to_string(),
+        content: "Regulators remain cautious about the new rules.".to_string(),
+        language: Some("en".to_string()),
+        ..Default::default()
+    }).await.unwrap();
+    DataProcessorService::new(price_holder, news_holder.clone(), config).process_data().await.unwrap();
+
+    let processed = news_holder.get().await.unwrap();
+    assert_eq!(processed.len(), 1);
+    let etf = processed[0].aspects.iter().find(|a| a.aspect == "etf").unwrap();
+    assert!(etf.score > 0.0);
+    assert!(processed[0].aspects.iter().any(|a| a.aspect == "regulation"));
+
+    let summary = summarize_aspects(&processed);
+    assert_eq!(summary.len(), 2);
+    assert_eq!(summary[0].article_count, 1);
+    assert_eq!(summary[0].share_of_articles, 100.0);
+    assert_eq!(summary.iter().find(|s| s.aspect == "etf").unwrap().positive_count, 1);
+}
+
+#[test]
+fn test_entity_extraction_and_summary() {
+    use btc_news_analyzer::analytics::{summarize_entities, EntityExtractor};
+
+    let mut config = EntityConfig::default();
+    config.gazetteer.push(EntityDefinition {
+        name: "Strike".to_string(),
+        kind: "company".to_string(),
+        aliases: vec!["Strike app".to_string()],
+    });
+    let extractor = EntityExtractor::new(&config);
+
+    let entities = extractor.extract(
+        "SEC chair Gary Gensler met BlackRock. Gensler said the SEC will review IBIT. \
+         CEO Jack Mallers of the Strike app and Donald Trump spoke; $SOL and BTCX ETF rallied. \
+         Traders say the sec of delay matters. The Fed paused; miners fed coins into exchanges, fed up with fees.",
+    );
+    let find = |name: &str| entities.iter().find(|e| e.name == name);
+
+    // Аббревиатура SEC — с учётом регистра, "sec" в тексте не засчитывается
+    assert_eq!(find("SEC").unwrap().mentions, 2);
+    // Глагол "fed" — не упоминание ФРС
+    assert_eq!(find("Federal Reserve").unwrap().mentions, 1);
+    assert_eq!(find("Gary Gensler").unwrap().mentions, 2);
+    assert_eq!(find("Donald Trump").unwrap().mentions, 1);
+    assert_eq!(find("IBIT").unwrap().kind, "etf");
+    assert_eq!(find("Strike").unwrap().kind, "company");
+    assert_eq!(find("Jack Mallers").unwrap().kind, "person");
+    assert_eq!(find("SOL").unwrap().kind, "asset");
+    assert_eq!(find("BTCX").unwrap().kind, "etf");
+    assert_eq!(entities[0].mentions, 2);
+
+    let news = |sentiment: f64, text: &str| BitcoinNews {
+        sentiment_score: Some(sentiment),
+        entities: extractor.extract(text),
+        ..Default::default()
+    };
+    let items = vec![
+        news(0.6, "SEC approves options on IBIT"),
+        news(-0.2, "SEC sues Binance, Binance responds"),
+        news(0.4, "Binance lists new pairs"),
+    ];
+    let summary = summarize_entities(&items, 2);
+    assert_eq!(summary.len(), 2);
+    assert_eq!(summary[0].name, "Binance");
+    assert_eq!(summary[0].mention_count, 3);
+    assert!((summary[0].average_sentiment - 0.1).abs() < 1e-9);
+    assert_eq!(summary[1].name, "SEC");
+    assert!((summary[1].average_sentiment - 0.2).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_full_article_extraction_and_fetch() {
+    use axum::{response::Html, routing::get, Router};
+    use btc_news_analyzer::analytics::extract_main_text;
+
+    const PAGE: &str = r#"<!doctype html><html><head><title>ETF flows</title>
+        <script>var tracking = "Bitcoin tracking script that should never be extracted";</script></head>
+        <body>
+        <nav><p>Markets News Opinion Research Podcasts Newsletters and Events</p></nav>
+        <div class="layout">
+          <div class="article-body">
+            <p>Spot bitcoin ETFs recorded their largest weekly inflows since March, led by BlackRock.</p>
+            <p>Analysts said the demand came mostly from advisers rebalancing client portfolios &amp; pensions.</p>
+          </div>
+          <div class="article-body">
+            <p>Bitcoin climbed above $70,000 on Friday as the dollar weakened against major currencies.</p>
+          </div>
+          <div class="related-stories"><p>Read more: Ether ETF filings pile up as issuers race for approval</p></div>
+          <p><a href="/a">Subscribe</a> <a href="/b">to our daily newsletter for the latest news</a></p>
+        </div>
+        <footer><p>Copyright 2025 Example Media. All rights reserved worldwide.</p></footer>
+        </body></html>"#;
+
+    let text = extract_main_text(PAGE).unwrap();
+    let paragraphs: Vec<&str> = text.split("\n\n").collect();
+    assert_eq!(paragraphs.len(), 3);
+    assert!(paragraphs[1].contains("client portfolios & pensions"));
+    assert!(!text.contains("tracking script"));
+    assert!(!text.contains("Read more"));
+    assert!(!text.contains("Copyright"));
+    assert!(extract_main_text("<html><body><p>Too short</p></body></html>").is_none());
+
+    // Классы <body> и части имён классов не считаются служебными блоками
+    let page = PAGE
+        .replace("<body>", r#"<body class="has-cookie-banner comments-enabled">"#)
+        .replace(r#"<div class="layout">"#, r#"<div class="layout shareable-story">"#);
+    assert_eq!(extract_main_text(&page).unwrap(), text);
+
+    let app = Router::new()
+        .route("/article", get(|| async { Html(PAGE) }))
+        .route("/huge", get(|| async { Html(format!("<p>{}</p>", "bitcoin ".repeat(10_000))) }));
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(axum::Server::from_tcp(listener).unwrap().serve(app.into_make_service()));
+
+    let fetcher = ArticleFetcher::new(ArticleFetchConfig {
+        enabled: true,
+        min_text_chars: 100,
+        max_bytes: 8 * 1024,
+        ..Default::default()
+    });
+    let long_snippet = "Bitcoin ".repeat(200);
+    let mut news = vec![
+        BitcoinNews {
+            content: "Spot bitcoin ETFs recorded their largest weekly inflows… [+2312 chars]".to_string(),
+            url: Some(format!("http://{}/article", address)),
+            ..Default::default()
+        },
+        // Достаточно длинный фрагмент не загружается повторно
+        BitcoinNews { content: long_snippet.clone(), url: Some(format!("http://{}/article", address)), ..Default::default() },
+        BitcoinNews { content: "short".to_string(), url: Some(format!("http://{}/missing", address)), ..Default::default() },
+        BitcoinNews { content: "short".to_string(), url: Some(format!("http://{}/huge", address)), ..Default::default() },
+    ];
+
+    assert_eq!(fetcher.enrich(&mut news).await, 2);
+    assert_eq!(news[0].full_text.as_deref(), Some(text.as_str()));
+    assert!(news[1].full_text.is_none());
+    assert!(news[2].full_text.is_none());
+    // Страница обрезана до max_bytes, но текст из прочитанной части извлечён
+    assert!(news[3].full_text.as_ref().unwrap().len() < 8 * 1024);
+}
+
+#[tokio::test]
+async fn test_relevance_scoring_and_weighting() {
+    use btc_news_analyzer::analytics::RelevanceScorer;
+
+    let config = RelevanceConfig::default();
+    let scorer = RelevanceScorer::new(&["bitcoin".to_string(), "btc".to_string(), "crypto".to_string()], &config);
+    let etf = vec![EntityMention { name: "IBIT".to_string(), kind: "etf".to_string(), mentions: 2 }];
+
+    let focused = scorer.score(
+        "Bitcoin ETF inflows hit record",
+        "Bitcoin ETF inflows hit record. BTC rallied as IBIT took in $1B; bitcoin traders cheered.",
+        &etf,
+    );
+    let passing = scorer.score(
+        "Stocks close higher on tech earnings",
+        "Stocks close higher on tech earnings. Apple and Microsoft led gains, oil slipped, \
+         the dollar was flat and bond yields edged lower ahead of the jobs report. \
+         Some analysts also mentioned crypto.",
+        &[],
+    );
+    let unrelated = scorer.score("Oil slips", "Oil slips as supply rises", &[]);
+    assert!(focused > 0.8, "focused = {}", focused);
+    assert!(passing > 0.0 && passing < 0.25, "passing = {}", passing);
+    assert_eq!(unrelated, 0.0);
+    // Совпадение внутри другого слова не засчитывается
+    assert_eq!(scorer.score("Bitcoiners", "Bitcoiners and cryptography", &[]), 0.0);
+
+    // Взвешивание sentiment_score по релевантности
+    let price_holder = BitcoinPriceHolder::new();
+    let news_holder = BitcoinNewsHolder::new();
+    let amount_days = std::sync::Arc::new(tokio::sync::Mutex::new(AmountDays { days: 2 }));
+    for (day, price) in [(19, 100.0), (20, 101.0)] {
+        price_holder.add(BitcoinPrice { date: NaiveDate::from_ymd_opt(2025, 8, day).unwrap(), price }).await.unwrap();
+    }
+    for (is_positive, relevance) in [(true, 0.9), (false, 0.1), (false, 0.2)] {
+        news_holder.add(BitcoinNews {
+            title: "news".to_string(),
+            is_positive: Some(is_positive),
+            relevance: Some(relevance),
+            ..Default::default()
+        }).await.unwrap();
+    }
+
+    let unweighted = DataMakerDecisionService::new(price_holder.clone(), news_holder.clone(), amount_days.clone(), test_config());
+    let score = unweighted.make_decision().await.unwrap().news_statistics.sentiment_score;
+    assert!((score - (-1.0 / 3.0)).abs() < 1e-9);
+
+    let mut config = test_config();
+    config.relevance.weight_sentiment = true;
+    let weighted = DataMakerDecisionService::new(price_holder, news_holder, amount_days, config);
+    let result = weighted.make_decision().await.unwrap();
+    assert!((result.news_statistics.sentiment_score - 0.5).abs() < 1e-9);
+    assert_eq!(result.key_news[0].relevance, Some(0.9));
+
+    let mut invalid = RelevanceConfig { min_relevance: 1.5, ..Default::default() };
+    assert!(invalid.validate().is_err());
+    invalid.min_relevance = 0.3;
+    invalid.enabled = false;
+    assert!(invalid.validate().is_err());
+}
+
+#[tokio::test]
+async fn test_newsapi_pagination_and_quota() {
+    use axum::{extract::Query, http::StatusCode, routing::get, Json, Router};
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+
+    fn page(params: &HashMap<String, String>) -> Json<Value> {
+        let page: usize = params["page"].parse().unwrap();
+        let size: usize = params["pageSize"].parse().unwrap();
+        let articles: Vec<Value> = (0..size)
+            .map(|i| json!({
+                "title": format!("Bitcoin update {}-{}", page, i),
+                "content": "Bitcoin price moves",
+                "url": format!("https://example.com/{}/{}", page, i),
+                "publishedAt": "2025-08-20T10:00:00Z",
+            }))
+            .collect();
+        Json(json!({"status": "ok", "totalResults": 250, "articles": articles}))
+    }
+
+    let app = Router::new()
+        .route("/paged", get(|Query(params): Query<HashMap<String, String>>| async move {
+            if params["page"] == "3" {
+                let error = json!({"status": "error", "code": "maximumResultsReached", "message": "Developer accounts are limited"});
+                return Err((StatusCode::UPGRADE_REQUIRED, Json(error)));
+            }
+            Ok(page(&params))
+        }))
+        .route("/limited", get(|Query(params): Query<HashMap<String, String>>| async move {
+            if params["page"] == "2" {
+                let error = json!({"status": "error", "code": "rateLimited", "message": "You have made too many requests"});
+                return Err((StatusCode::TOO_MANY_REQUESTS, Json(error)));
+            }
+            Ok(page(&params))
+        }));
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(axum::Server::from_tcp(listener).unwrap().serve(app.into_make_service()));
+
+    let collect = |path: &str, daily_request_limit: usize| {
+        let mut config = test_config();
+        config.newsapi_url = format!("http://{}/{}", address, path);
+        config.max_articles = Some(100);
+        config.newsapi = NewsApiConfig {
+            page_size: 10,
+            max_results: 1000,
+            daily_request_limit,
+            ..Default::default()
+        };
+        let news_holder = BitcoinNewsHolder::new();
+        let amount_days = std::sync::Arc::new(tokio::sync::Mutex::new(AmountDays { days: 45 }));
+        let collector = DataCollectorService::new(BitcoinPriceHolder::new(), news_holder.clone(), amount_days, config);
+        async move {
+            collector.collect_bitcoin_news().await.unwrap();
+            (news_holder.len().await.unwrap(), collector.newsapi_usage().await.unwrap())
+        }
+    };
+
+    // Страницы запрашиваются до maximumResultsReached, период ограничен тарифом
+    let (collected, usage) = collect("paged", 0).await;
+    assert_eq!(collected, 20);
+    assert_eq!(usage.requests_made, 3);
+    assert_eq!(usage.requests_remaining, None);
+    assert_eq!(usage.total_results, 250);
+    assert_eq!(usage.articles_received, 20);
+    assert_eq!(usage.stop_reason.as_deref(), Some("maximumResultsReached"));
+    assert_eq!(usage.history_days, 30);
+    assert!(usage.history_limited);
+
+    // Суточный лимит запросов
+    let (collected, usage) = collect("paged", 2).await;
+    assert_eq!(collected, 20);
+    assert_eq!(usage.requests_made, 2);
+    assert_eq!(usage.requests_remaining, Some(0));
+    assert_eq!(usage.stop_reason.as_deref(), Some("daily_request_limit"));
+
+    // rateLimited: собранное сохраняется, квота считается исчерпанной
+    let (collected, usage) = collect("limited", 50).await;
+    assert_eq!(collected, 10);
+    assert_eq!(usage.requests_made, 2);
+    assert_eq!(usage.requests_today, 2);
+    assert_eq!(usage.requests_remaining, Some(0));
+    assert_eq!(usage.stop_reason.as_deref(), Some("rateLimited"));
+}
+
+#[tokio::test]
+async fn test_newsapi_maximum_results_on_first_page() {
+    use axum::{http::StatusCode, routing::get, Json, Router};
+    use serde_json::json;
+
+    // Предел тарифа уже на первой странице: выдача закончена, это не отказ источника
+    let app = Router::new().route("/newsapi", get(|| async {
+        let error = json!({"status": "error", "code": "maximumResultsReached", "message": "Developer accounts are limited"});
+        (StatusCode::UPGRADE_REQUIRED, Json(error))
+    }));
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(axum::Server::from_tcp(listener).unwrap().serve(app.into_make_service()));
+
+    let mut config = test_config();
+    config.newsapi_url = format!("http://{}/newsapi", address);
+    config.news_sources = NewsSourcesConfig { order: vec!["newsapi".to_string()], combine: false };
+    let news_holder = BitcoinNewsHolder::new();
+    let amount_days = std::sync::Arc::new(tokio::sync::Mutex::new(AmountDays { days: 7 }));
+    let collector = DataCollectorService::new(BitcoinPriceHolder::new(), news_holder.clone(), amount_days, config);
+
+    collector.collect_bitcoin_news().await.unwrap();
+    let usage = collector.newsapi_usage().await.unwrap();
+    assert_eq!(news_holder.len().await.unwrap(), 0);
+    assert_eq!(usage.requests_made, 1);
+    assert_eq!(usage.articles_received, 0);
+    assert_eq!(usage.stop_reason.as_deref(), Some("maximumResultsReached"));
+}
+
+#[tokio::test]
+async fn test_reddit_source_and_social_statistics() {
+    use axum::{routing::get, Json, Router};
+    use serde_json::json;
+
+    let now = chrono::Utc::now().timestamp() as f64;
+    let post = |title: &str, score: i64, comments: u64, created_utc: f64, stickied: bool| json!({
+        "kind": "t3",
+        "data": {
+            "title": title,
+            "selftext": "",
+            "score": score,
+            "num_comments": comments,
+            "created_utc": created_utc,
+            "stickied": stickied,
+            "permalink": format!("/r/test/comments/{}/", score),
+        }
+    });
+    let bitcoin = json!({"data": {"children": [
+        post("Daily Discussion", 500, 900, now, true),
+        post("Just stacked more sats", 50, 10, now - 3600.0, false),
+        post("Low effort post", 3, 10, now, false),
+        post("Old news", 80, 40, 1_577_836_800.0, false),
+    ]}});
+    let cryptocurrency = json!({"data": {"children": [
+        post("Solana hits a new high", 100, 20, now, false),
+        post("Bitcoin dominance rises again", 40, 12, now, false),
+    ]}});
+    let app = Router::new()
+        .route("/r/Bitcoin/new.json", get(move || async move { Json(bitcoin) }))
+        .route("/r/CryptoCurrency/new.json", get(move || async move { Json(cryptocurrency) }))
+        .route("/everything", get(|| async {
+            Json(json!({"status": "ok", "totalResults": 1, "articles": [
+                {"title": "Bitcoin ETF inflows grow", "content": "Bitcoin funds", "url": "https://news.example.com/a"}
+            ]}))
+        }));
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(axum::Server::from_tcp(listener).unwrap().serve(app.into_make_service()));
+
+    let mut config = test_config();
+    config.newsapi_url = format!("http://{}/everything", address);
+    config.reddit = RedditConfig {
+        enabled: true,
+        base_url: format!("http://{}", address),
+        ..Default::default()
+    };
+    let news_holder = BitcoinNewsHolder::new();
+    let amount_days = std::sync::Arc::new(tokio::sync::Mutex::new(AmountDays { days: 7 }));
+    let collector = DataCollectorService::new(BitcoinPriceHolder::new(), news_holder.clone(), amount_days.clone(), config.clone());
+    collector.collect_bitcoin_news().await.unwrap();
+
+    let mut news = news_holder.get().await.unwrap();
+    let social: Vec<&str> = news.iter()
+        .filter(|n| n.source_kind == SourceKind::Social)
+        .map(|n| n.title.as_str())
+        .collect();
+    assert_eq!(social, ["Just stacked more sats", "Bitcoin dominance rises again"]);
+    assert_eq!(news.iter().filter(|n| n.source_kind == SourceKind::Editorial).count(), 1);
+    assert!(news[1].url.as_ref().unwrap().starts_with(&format!("http://{}/r/test/comments/", address)));
+    assert!(news[1].published_at.is_some());
+
+    // Статистика настроений отдельно по редакционным новостям и соцсетям
+    for (item, is_positive) in news.iter_mut().zip([true, false, false]) {
+        item.is_positive = Some(is_positive);
+    }
+    let price_holder = BitcoinPriceHolder::new();
+    for (day, price) in [(19, 100.0), (20, 101.0)] {
+        price_holder.add(BitcoinPrice { date: NaiveDate::from_ymd_opt(2025, 8, day).unwrap(), price }).await.unwrap();
+    }
+    news_holder.clear().await.unwrap();
+    for item in news {
+        news_holder.add(item).await.unwrap();
+    }
+    let decision = DataMakerDecisionService::new(price_holder, news_holder, amount_days, config);
+    let by_source_kind = decision.make_decision().await.unwrap().news_statistics.by_source_kind;
+    assert_eq!(by_source_kind.len(), 2);
+    assert_eq!(by_source_kind[0].source_kind, "social");
+    assert_eq!(by_source_kind[0].total, 2);
+    assert_eq!(by_source_kind[0].sentiment_score, -1.0);
+    assert_eq!(by_source_kind[1].source_kind, "editorial");
+    assert_eq!(by_source_kind[1].sentiment_score, 1.0);
+}
+
+#[tokio::test]
+async fn test_gdelt_and_json_news_sources() {
+    use axum::{extract::Query as UrlQuery, routing::get, Json, Router};
+    use btc_news_analyzer::analytics::Query;
+    use serde_json::{json, Value};
+    use std::collections::HashMap;
+
+    let query = Query::parse(r#"(bitcoin OR "spot etf") -ethereum NOT (scam OR hack)"#).unwrap();
+    assert_eq!(query.to_gdelt(), r#"(bitcoin OR "spot etf") -ethereum"#);
+
+    let app = Router::new()
+        .route("/gdelt", get(|UrlQuery(params): UrlQuery<HashMap<String, String>>| async move {
+            assert_eq!(params["query"], "(bitcoin OR crypto)");
+            assert_eq!(params["timespan"], "90d");
+            let body: Value = if params["mode"] == "ToneChart" {
+                json!({"tonechart": [
+                    {"bin": -4, "count": 1, "toparts": [{"url": "https://www.coindesk.com/a", "title": "Bitcoin slides"}]},
+                    {"bin": 3, "count": 1, "toparts": [{"url": "https://decrypt.co/b", "title": "Bitcoin ETF demand"}]}
+                ]})
+            } else {
+                json!({"articles": [
+                    {"url": "https://www.coindesk.com/a", "title": "Bitcoin slides below $60,000", "seendate": "20250820T101500Z",
+                     "domain": "coindesk.com", "language": "English", "sourcecountry": "United States"},
+                    {"url": "https://decrypt.co/b", "title": "Bitcoin ETF demand returns", "seendate": "20250819T080000Z",
+                     "domain": "decrypt.co", "language": "Russian"},
+                    {"url": "https://example.com/c", "title": "Crypto markets wrap", "seendate": "20250819T070000Z", "domain": "example.com"}
+                ]})
+            };
+            Json(body)
+        }))
+        .route("/custom", get(|UrlQuery(params): UrlQuery<HashMap<String, String>>| async move {
+            assert_eq!(params["q"], "bitcoin OR crypto");
+            Json(json!({"data": {"items": [
+                {"headline": "Bitcoin miners expand", "body": "Hashrate record", "link": "https://miners.example/1",
+                 "time": 1755684000, "publisher": {"name": "Miner Weekly"}, "sentiment": "0.4"},
+                {"headline": "Gold hits record", "body": "Precious metals rally", "link": "https://gold.example/2"},
+                {"body": "No headline"}
+            ]}}))
+        }));
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(axum::Server::from_tcp(listener).unwrap().serve(app.into_make_service()));
+
+    let mut config = test_config();
+    config.gdelt.base_url = format!("http://{}/gdelt", address);
+    config.json_sources = vec![JsonSourceConfig {
+        name: "custom".to_string(),
+        url: format!("http://{}/custom?q={{query}}&from={{from}}", address),
+        items_path: "data.items".to_string(),
+        title_path: "headline".to_string(),
+        content_path: Some("body".to_string()),
+        url_path: Some("link".to_string()),
+        published_path: Some("time".to_string()),
+        source_path: Some("publisher.name".to_string()),
+        tone_path: Some("sentiment".to_string()),
+        ..Default::default()
+    }];
+    config.news_sources = NewsSourcesConfig {
+        order: vec!["gdelt".to_string(), "custom".to_string()],
+        combine: true,
+    };
+    assert!(config.validate().is_ok());
+
+    let news_holder = BitcoinNewsHolder::new();
+    let amount_days = std::sync::Arc::new(tokio::sync::Mutex::new(AmountDays { days: 120 }));
+    let collector = DataCollectorService::new(BitcoinPriceHolder::new(), news_holder.clone(), amount_days, config.clone());
+    collector.collect_bitcoin_news().await.unwrap();
+
+    let news = news_holder.get().await.unwrap();
+    assert_eq!(news.len(), 4);
+    assert_eq!(news[0].source.as_deref(), Some("coindesk.com"));
+    assert_eq!(news[0].source_tone, Some(-4.0));
+    assert_eq!(news[0].published_at.as_deref(), Some("2025-08-20T10:15:00+00:00"));
+    assert_eq!(news[0].language.as_deref(), Some("en"));
+    assert_eq!(news[1].language.as_deref(), Some("ru"));
+    assert_eq!(news[2].source_tone, None);
+
+    // JSON-источник: поля по путям, фильтр по запросу отбросил новость о золоте
+    assert_eq!(news[3].title, "Bitcoin miners expand");
+    assert_eq!(news[3].content, "Hashrate record");
+    assert_eq!(news[3].url.as_deref(), Some("https://miners.example/1"));
+    assert_eq!(news[3].source.as_deref(), Some("Miner Weekly"));
+    assert_eq!(news[3].source_tone, Some(0.4));
+    assert_eq!(news[3].published_at.as_deref(), Some("2025-08-20T10:00:00+00:00"));
+
+    config.news_sources.order.push("unknown".to_string());
+    assert!(config.validate().is_err());
+}
+
+#[tokio::test]
+async fn test_file_news_and_price_sources() {
+    let directory = std::env::temp_dir().join(format!("btc_files_test_{}", std::process::id()));
+    let nested = directory.join("nested");
+    std::fs::create_dir_all(&nested).unwrap();
+    std::fs::write(directory.join("news.jsonl"), concat!(
+        r#"{"title": "Bitcoin ETF inflows hit record", "content": "Spot ETF demand", "url": "https://a.example/1", "publishedAt": "2025-08-20T10:00:00Z", "source": {"id": null, "name": "CoinDesk"}, "lang": "en"}"#, "\n",
+        r#"{"headline": "Bitcoin miners sell reserves", "body": "Hashprice falls", "content": "Miners sell reserves as hashprice falls", "date": "2025-08-01", "published_at": "2025-08-02T09:00:00Z"}"#, "\n",
+        "\n",
+        r#"{"title": "Gold rallies", "content": "Precious metals", "published_at": "2025-08-19T10:00:00Z"}"#, "\n",
+    )).unwrap();
+    std::fs::write(directory.join("archive.csv"), "Title,Text,Link,Date,Source_Kind\n\
+        Crypto traders turn bullish,Bitcoin funding rates rise,https://b.example/2,2025-08-18 12:00:00,social\n\
+        Bitcoin without date,Undated archive item,,,\n").unwrap();
+    std::fs::write(directory.join("feed.rss"), r#"<?xml version="1.0"?><rss version="2.0"><channel><title>Feed</title>
+        <item><title>Bitcoin breaks resistance</title><description>Bulls take control</description><link>https://c.example/3</link>
+        <pubDate>Tue, 19 Aug 2025 08:00:00 GMT</pubDate></item></channel></rss>"#).unwrap();
+    std::fs::write(directory.join("notes.txt"), "Bitcoin notes are ignored").unwrap();
+    std::fs::write(directory.join("broken.json"), "{not json").unwrap();
+    std::fs::write(nested.join("nested.jsonl"), r#"{"title": "Bitcoin nested file", "date": "2025-08-20"}"#).unwrap();
+
+    let prices_csv = directory.join("prices.csv");
+    std::fs::write(&prices_csv, "Open Time,Open,High,Low,Close,Volume\n\
+        2025-08-17,60000,61000,59000,60500,100\n\
+        2025-08-18 00:00:00,60500,62000,60000,61000,100\n\
+        2025-08-18 12:00:00,61000,62500,60500,61800,100\n\
+        2025-08-19,61800,63000,61000,62500,100\n\
+        bad row,1,1,1,oops,1\n\
+        1755648000,62500,64000,62000,63500,100\n").unwrap();
+
+    let mut config = test_config();
+    config.news_sources.order = vec!["files".to_string()];
+    assert!(config.validate().is_err());
+    // Без NewsAPI в порядке источников ключ не нужен
+    config.newsapi_key = String::new();
+    config.files = FileSourcesConfig {
+        news_directory: Some(directory.to_string_lossy().to_string()),
+        prices_csv: Some(prices_csv.to_string_lossy().to_string()),
+        ..Default::default()
+    };
+    assert!(config.validate().is_ok());
+    let mut with_newsapi = config.clone();
+    with_newsapi.news_sources.order.push("newsapi".to_string());
+    assert!(with_newsapi.validate().is_err());
+
+    // Период из трёх дней до последней даты архива (20 августа); новость без даты сохраняется
+    let news_holder = BitcoinNewsHolder::new();
+    let price_holder = BitcoinPriceHolder::new();
+    let amount_days = std::sync::Arc::new(tokio::sync::Mutex::new(AmountDays { days: 3 }));
+    let collector = DataCollectorService::new(price_holder.clone(), news_holder.clone(), amount_days, config.clone());
+    collector.collect_data().await.unwrap();
+
+    let news = news_holder.get().await.unwrap();
+    let titles: Vec<&str> = news.iter().map(|n| n.title.as_str()).collect();
+    assert_eq!(titles, vec![
+        "Bitcoin ETF inflows hit record",
+        "Bitcoin breaks resistance",
+        "Crypto traders turn bullish",
+        "Bitcoin without date",
+    ]);
+    assert_eq!(news[0].source.as_deref(), Some("CoinDesk"));
+    assert_eq!(news[0].language.as_deref(), Some("en"));
+    assert_eq!(news[1].url.as_deref(), Some("https://c.example/3"));
+    assert_eq!(news[2].source_kind, SourceKind::Social);
+    assert_eq!(news[2].published_at.as_deref(), Some("2025-08-18T12:00:00+00:00"));
+    assert_eq!(news[3].published_at, None);
+
+    // Внутридневные строки свёрнуты в последнюю цену дня, 17 августа вне периода
+    let prices = price_holder.get().await.unwrap();
+    let closes: Vec<(NaiveDate, f64)> = prices.iter().map(|p| (p.date, p.price)).collect();
+    assert_eq!(closes, vec![
+        (NaiveDate::from_ymd_opt(2025, 8, 18).unwrap(), 61800.0),
+        (NaiveDate::from_ymd_opt(2025, 8, 19).unwrap(), 62500.0),
+        (NaiveDate::from_ymd_opt(2025, 8, 20).unwrap(), 63500.0),
+    ]);
+
+    // Рекурсивный обход и явный конец периода
+    config.files.recursive = true;
+    config.files.end_date = NaiveDate::from_ymd_opt(2025, 8, 2);
+    let news = FileNewsSource::new(config.files.clone()).read().unwrap();
+    assert_eq!(news.len(), 7);
+    // При нескольких подходящих полях берётся первое по приоритету, а не по алфавиту
+    let miners = news.iter().find(|n| n.title == "Bitcoin miners sell reserves").unwrap();
+    assert_eq!(miners.content, "Miners sell reserves as hashprice falls");
+    assert_eq!(miners.published_at.as_deref(), Some("2025-08-02T09:00:00+00:00"));
+    let news_holder = BitcoinNewsHolder::new();
+    let amount_days = std::sync::Arc::new(tokio::sync::Mutex::new(AmountDays { days: 3 }));
+    let mut config_limited = config.clone();
+    let collector = DataCollectorService::new(BitcoinPriceHolder::new(), news_holder.clone(), amount_days, config);
+    collector.collect_bitcoin_news().await.unwrap();
+    let titles: Vec<String> = news_holder.get().await.unwrap().into_iter().map(|n| n.title).collect();
+    assert_eq!(titles, vec!["Bitcoin miners sell reserves", "Bitcoin without date"]);
+
+    // max_articles оставляет самые свежие новости, а не первые по порядку файлов
+    config_limited.files.end_date = None;
+    config_limited.max_articles = Some(2);
+    let news_holder = BitcoinNewsHolder::new();
+    let amount_days = std::sync::Arc::new(tokio::sync::Mutex::new(AmountDays { days: 30 }));
+    let collector = DataCollectorService::new(BitcoinPriceHolder::new(), news_holder.clone(), amount_days, config_limited);
+    collector.collect_bitcoin_news().await.unwrap();
+    let titles: Vec<String> = news_holder.get().await.unwrap().into_iter().map(|n| n.title).collect();
+    assert_eq!(titles, vec!["Bitcoin ETF inflows hit record", "Bitcoin nested file"]);
+
+    std::fs::remove_dir_all(&directory).unwrap();
+}
+
+#[tokio::test]
+async fn test_configurable_endpoints() {
+    use axum::{extract::Query as UrlQuery, http::StatusCode, routing::get, Json, Router};
+    use serde_json::json;
+    use std::collections::HashMap;
+
+    let rss = r#"<?xml version="1.0"?><rss version="2.0"><channel><title>Mirror</title>
+        <item><title>Bitcoin rallies past resistance</title><description>Bitcoin buyers return</description>
+        <link>https://mirror.example/1</link></item>
+        <item><title>Gold steady</title><description>Metals flat</description></item></channel></rss>"#;
+    let app = Router::new()
+        .route("/coingecko/coins/bitcoin/market_chart", get(|| async { StatusCode::SERVICE_UNAVAILABLE }))
+        .route("/binance/klines", get(|UrlQuery(params): UrlQuery<HashMap<String, String>>| async move {
+            assert_eq!(params["symbol"], "BTCUSDT");
+            assert_eq!(params["limit"], "2");
+            Json(json!([
+                [1755561600000_i64, "60000", "61000", "59000", "60500", "100"],
+                [1755648000000_i64, "60500", "62000", "60000", "61500", "100"]
+            ]))
+        }))
+        .route("/feed", get(move || async move { rss }));
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    tokio::spawn(axum::Server::from_tcp(listener).unwrap().serve(app.into_make_service()));
+
+    let mut config = test_config();
+    config.endpoints = EndpointsConfig {
+        coingecko_url: format!("http://{}/coingecko", address),
+        binance_url: format!("http://{}/binance/", address),
+        coincap_url: format!("http://{}/coincap", address),
+        rss_feeds: vec![format!("http://{}/feed", address)],
+    };
+    config.news_sources.order = vec!["rss".to_string()];
+    assert!(config.validate().is_ok());
+
+    let price_holder = BitcoinPriceHolder::new();
+    let news_holder = BitcoinNewsHolder::new();
+    let amount_days = std::sync::Arc::new(tokio::sync::Mutex::new(AmountDays { days: 2 }));
+    let collector = DataCollectorService::new(price_holder.clone(), news_holder.clone(), amount_days, config.clone());
+
+    let urls = collector.price_api_urls(2);
+    assert_eq!(urls[1], ("binance", format!("http://{}/binance/klines?symbol=BTCUSDT&interval=1d&limit=2", address)));
+    assert!(urls[2].1.starts_with(&format!("http://{}/coincap/assets/bitcoin/history?", address)));
+
+    // CoinGecko недоступен — цены берутся из зеркала Binance
+    collector.collect_data().await.unwrap();
+    let prices: Vec<f64> = price_holder.get().await.unwrap().iter().map(|p| p.price).collect();
+    assert_eq!(prices, vec![60500.0, 61500.0]);
+    let news = news_holder.get().await.unwrap();
+    assert_eq!(news.len(), 1);
+    assert_eq!(news[0].url.as_deref(), Some("https://mirror.example/1"));
+
+    config.endpoints.rss_feeds.clear();
+    assert!(config.validate().is_err());
+    config.endpoints = EndpointsConfig { binance_url: "api.binance.com".to_string(), ..Default::default() };
+    assert!(config.validate().is_err());
+}